@@ -0,0 +1,271 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! JSON-RPC interface of the crowdloan rewards pallet, wrapping its runtime API
+//!
+//! Balances are serialized as decimal strings, as u128 values do not fit in a JavaScript number
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+};
+use pallet_crowdloan_rewards::{
+	runtime_api::CrowdloanRewardsApi as CrowdloanRewardsRuntimeApi, RelayAccountStatus,
+	RewardEntryStatus,
+};
+use parity_scale_codec::Codec;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::{fmt::Display, marker::PhantomData, sync::Arc};
+
+/// A reward entry along with its vesting status
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardInfo<RelayChainAccountId, VestingBlockNumber> {
+	pub total_reward: String,
+	pub claimed_reward: String,
+	pub contributed_relay_addresses: Vec<RelayChainAccountId>,
+	pub vesting_start: VestingBlockNumber,
+	pub vesting_end: VestingBlockNumber,
+	pub vested_reward: String,
+	pub claimable_reward: String,
+}
+
+impl<RelayChainAccountId, Balance: Display, VestingBlockNumber>
+	From<RewardEntryStatus<RelayChainAccountId, Balance, VestingBlockNumber>>
+	for RewardInfo<RelayChainAccountId, VestingBlockNumber>
+{
+	fn from(status: RewardEntryStatus<RelayChainAccountId, Balance, VestingBlockNumber>) -> Self {
+		RewardInfo {
+			total_reward: status.total_reward.to_string(),
+			claimed_reward: status.claimed_reward.to_string(),
+			contributed_relay_addresses: status.contributed_relay_addresses,
+			vesting_start: status.vesting_start,
+			vesting_end: status.vesting_end,
+			vested_reward: status.vested_reward.to_string(),
+			claimable_reward: status.claimable_reward.to_string(),
+		}
+	}
+}
+
+/// The vesting window of a reward entry
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VestingWindow<VestingBlockNumber> {
+	pub start: VestingBlockNumber,
+	pub end: VestingBlockNumber,
+}
+
+/// Whether a relay account contributed, and if so whether it is associated already
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum RelayStatus {
+	Unknown,
+	Unassociated { reward: String },
+	Associated { reward: Option<String> },
+}
+
+impl<Balance: Display> From<RelayAccountStatus<Balance>> for RelayStatus {
+	fn from(status: RelayAccountStatus<Balance>) -> Self {
+		match status {
+			RelayAccountStatus::Unknown => RelayStatus::Unknown,
+			RelayAccountStatus::Unassociated(reward) => RelayStatus::Unassociated {
+				reward: reward.to_string(),
+			},
+			RelayAccountStatus::Associated(reward) => RelayStatus::Associated {
+				reward: reward.map(|reward| reward.to_string()),
+			},
+		}
+	}
+}
+
+#[rpc(client, server)]
+pub trait CrowdloanRewardsApi<BlockHash, AccountId, RelayChainAccountId, VestingBlockNumber> {
+	/// Reward vested but not claimed yet by the account, if it has a reward entry
+	#[method(name = "crowdloanRewards_claimable")]
+	fn claimable(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<Option<String>>;
+
+	/// The reward entry of the account along with its vesting status
+	#[method(name = "crowdloanRewards_rewardInfo")]
+	fn reward_info(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<RewardInfo<RelayChainAccountId, VestingBlockNumber>>>;
+
+	/// Whether the relay account contributed, and if so whether it is associated already
+	#[method(name = "crowdloanRewards_relayStatus")]
+	fn relay_status(
+		&self,
+		relay_account: RelayChainAccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<RelayStatus>;
+
+	/// The vesting window of the reward entry of the account
+	#[method(name = "crowdloanRewards_vestingWindow")]
+	fn vesting_window(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<VestingWindow<VestingBlockNumber>>>;
+}
+
+/// Error code returned when the runtime API call fails
+const RUNTIME_ERROR: i32 = 1;
+
+fn runtime_error(error: impl std::fmt::Debug) -> ErrorObjectOwned {
+	ErrorObject::owned(
+		RUNTIME_ERROR,
+		"Unable to query the crowdloan rewards",
+		Some(format!("{:?}", error)),
+	)
+}
+
+/// Implements the CrowdloanRewardsApi RPC on top of the runtime API
+pub struct CrowdloanRewards<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> CrowdloanRewards<C, Block, Balance> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, Balance> CrowdloanRewards<C, Block, Balance>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	fn at(&self, at: Option<Block::Hash>) -> Block::Hash {
+		at.unwrap_or_else(|| self.client.info().best_hash)
+	}
+}
+
+impl<C, Block, AccountId, RelayChainAccountId, Balance, VestingBlockNumber>
+	CrowdloanRewardsApiServer<Block::Hash, AccountId, RelayChainAccountId, VestingBlockNumber>
+	for CrowdloanRewards<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: CrowdloanRewardsRuntimeApi<
+		Block,
+		AccountId,
+		RelayChainAccountId,
+		Balance,
+		NumberFor<Block>,
+		VestingBlockNumber,
+	>,
+	AccountId: Codec + DeserializeOwned + Send + Sync + 'static,
+	RelayChainAccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Display + Send + Sync + 'static,
+	VestingBlockNumber: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+	fn claimable(&self, account: AccountId, at: Option<Block::Hash>) -> RpcResult<Option<String>> {
+		let entry = self
+			.client
+			.runtime_api()
+			.reward_entry(self.at(at), account)
+			.map_err(runtime_error)?;
+		Ok(entry.map(|entry| entry.claimable_reward.to_string()))
+	}
+
+	fn reward_info(
+		&self,
+		account: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<RewardInfo<RelayChainAccountId, VestingBlockNumber>>> {
+		let entry = self
+			.client
+			.runtime_api()
+			.reward_entry(self.at(at), account)
+			.map_err(runtime_error)?;
+		Ok(entry.map(Into::into))
+	}
+
+	fn relay_status(
+		&self,
+		relay_account: RelayChainAccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<RelayStatus> {
+		let status = self
+			.client
+			.runtime_api()
+			.relay_account_status(self.at(at), relay_account)
+			.map_err(runtime_error)?;
+		Ok(status.into())
+	}
+
+	fn vesting_window(
+		&self,
+		account: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<VestingWindow<VestingBlockNumber>>> {
+		let entry = self
+			.client
+			.runtime_api()
+			.reward_entry(self.at(at), account)
+			.map_err(runtime_error)?;
+		Ok(entry.map(|entry| VestingWindow {
+			start: entry.vesting_start,
+			end: entry.vesting_end,
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn balances_are_serialized_as_strings() {
+		let info: RewardInfo<u64, u32> = RewardEntryStatus {
+			total_reward: u128::MAX,
+			claimed_reward: 1u128,
+			contributed_relay_addresses: vec![7u64],
+			vesting_start: 1u32,
+			vesting_end: 10u32,
+			vested_reward: 2u128,
+			claimable_reward: 1u128,
+		}
+		.into();
+		assert_eq!(
+			serde_json::to_value(&info).unwrap(),
+			serde_json::json!({
+				"totalReward": "340282366920938463463374607431768211455",
+				"claimedReward": "1",
+				"contributedRelayAddresses": [7],
+				"vestingStart": 1,
+				"vestingEnd": 10,
+				"vestedReward": "2",
+				"claimableReward": "1",
+			})
+		);
+
+		let status: RelayStatus = RelayAccountStatus::Associated(Some(5u128)).into();
+		assert_eq!(
+			serde_json::to_value(&status).unwrap(),
+			serde_json::json!({ "status": "associated", "reward": "5" })
+		);
+	}
+}
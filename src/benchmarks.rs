@@ -1,7 +1,12 @@
 #![cfg(feature = "runtime-benchmarks")]
 
+use crate::relay_state::build_relay_state_proof;
+use crate::traits::{RelayStateRootProvider, RewardConversion};
 use crate::Config;
-use crate::{BalanceOf, Call, Pallet, WRAPPED_BYTES_POSTFIX, WRAPPED_BYTES_PREFIX};
+use crate::{
+	BalanceOf, Call, InitializationEntry, InitializationEntryOf, Pallet, Referrer, RewardCurveInfo,
+	MAX_REWARD_CURVE_TIERS, WRAPPED_BYTES_POSTFIX, WRAPPED_BYTES_PREFIX,
+};
 use ed25519_dalek::Signer;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
 use frame_support::traits::{Currency, Get, OnFinalize};
@@ -13,8 +18,8 @@ use sp_core::{
 	ed25519,
 };
 use sp_runtime::{
-	traits::{BlockNumberProvider, One},
-	MultiSignature,
+	traits::{BlockNumberProvider, One, UniqueSaturatedInto, Zero},
+	MultiSignature, Perbill,
 };
 use sp_std::vec;
 use sp_std::vec::Vec;
@@ -91,7 +96,7 @@ fn insert_contributors<T: Config>(
 fn close_initialization<T: Config>(
 	end_vesting_block: T::VestingBlockNumber,
 ) -> Result<(), &'static str> {
-	Pallet::<T>::complete_initialization(RawOrigin::Root.into(), end_vesting_block)?;
+	Pallet::<T>::complete_initialization(RawOrigin::Root.into(), end_vesting_block, None)?;
 	Ok(())
 }
 
@@ -120,6 +125,8 @@ fn max_batch_contributors<T: Config>() -> u32 {
 // This is our current number of contributors
 const MAX_ALREADY_USERS: u32 = 5799;
 const SEED: u32 = 999999999;
+// Bytes of a relay state proof beyond the nodes needed to read the contributions
+const MAX_EXTRA_PROOF_BYTES: u32 = 512 * 1024;
 
 benchmarks! {
 	initialize_reward_vec {
@@ -161,7 +168,7 @@ benchmarks! {
 		T::VestingBlockProvider::set_block_number(1u32.into());
 		Pallet::<T>::on_finalize(BlockNumberFor::<T>::one());
 
-	}:  _(RawOrigin::Root, 10u32.into())
+	}:  _(RawOrigin::Root, 10u32.into(), Some(1u32.into()))
 	verify {
 	  assert!(Pallet::<T>::initialized());
 	}
@@ -185,15 +192,24 @@ benchmarks! {
 		// Close initialization
 		close_initialization::<T>(10u32.into())?;
 
+		// Worst case: redistributed rewards are paid along with the claim, and so are the bonuses
+		let source = create_funded_user::<T>("source", SEED, 200u32.into());
+		Pallet::<T>::fund_pot(RawOrigin::Root.into(), source.clone(), 100u32.into())?;
+		Pallet::<T>::redistribute_excess(RawOrigin::Root.into(), 100u32.into())?;
+		Pallet::<T>::fund_bonus_pot(RawOrigin::Root.into(), source, 100u32.into())?;
+
 		// First inherent
 		T::VestingBlockProvider::set_block_number(1u32.into());
 		Pallet::<T>::on_finalize(BlockNumberFor::<T>::one());
 
-		// Create 4th relay block, by now the user should have vested some amount
-		T::VestingBlockProvider::set_block_number(4u32.into());
+		// Vesting end, the loyalty bonus is settled in this claim
+		T::VestingBlockProvider::set_block_number(10u32.into());
 	}:  _(RawOrigin::Signed(caller.clone()))
 	verify {
-	  assert_eq!(Pallet::<T>::accounts_payable(&caller).unwrap().total_reward, (100u32.into()));
+		let info = Pallet::<T>::accounts_payable(&caller).unwrap();
+		assert_eq!(info.total_reward, (100u32.into()));
+		assert!(info.bonus.loyalty_settled);
+		assert!(info.redistribution.pending.is_zero());
 	}
 
 	update_reward_address {
@@ -327,6 +343,473 @@ benchmarks! {
 
 	}
 
+	split_reward_entry {
+		// The weight will depend on the number of proofs provided and the number of splits
+		let x in 2..max_batch_contributors::<T>();
+		let y in 1..(max_batch_contributors::<T>() - 1);
+
+		// At least one relay account needs to remain in the split entry
+		let splits_len = y.min(x - 1);
+
+		// Fund pallet account
+		let total_pot = 100u32*x;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// The reward account whose entry will be split
+		let reward_account: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+
+		// Create x relay accounts
+		let mut relay_accounts: Vec<T::RelayChainAccountId> = Vec::new();
+		for i in 0..x {
+			let (relay_account, _) = create_sig::<T>(SEED-i, Vec::new());
+			relay_accounts.push(relay_account.into());
+		}
+
+		// All of them map to the same account
+		let mut contributors: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)> = Vec::new();
+		for relay_account in relay_accounts.clone() {
+			contributors.push((relay_account, Some(reward_account.clone()), 100u32.into()));
+		}
+
+		// Insert them
+		insert_contributors::<T>(contributors)?;
+
+		// Close initialization
+		close_initialization::<T>(10u32.into())?;
+
+		// First inherent
+		T::VestingBlockProvider::set_block_number(1u32.into());
+		Pallet::<T>::on_finalize(BlockNumberFor::<T>::one());
+
+		// Every split goes to a different account
+		let mut splits: Vec<(T::RelayChainAccountId, T::AccountId)> = Vec::new();
+		for i in 0..splits_len {
+			let new_account: T::AccountId = create_funded_user::<T>("user", SEED-1-i, 0u32.into());
+			splits.push((relay_accounts[i as usize].clone(), new_account));
+		}
+
+		// Create N sigs for N accounts
+		let payload = Pallet::<T>::split_reward_entry_payload(&reward_account, &splits);
+		let mut proofs: Vec<(T::RelayChainAccountId, MultiSignature)> = Vec::new();
+		for i in 0..x {
+			let (relay_account, signature) = create_sig::<T>(SEED-i, payload.clone());
+			proofs.push((relay_account.into(), signature));
+		}
+
+		// The caller is not the owner, so the proofs need to be verified
+		let caller: T::AccountId = create_funded_user::<T>("caller", SEED, 100u32.into());
+
+	}:  _(RawOrigin::Signed(caller), reward_account.clone(), splits.clone(), proofs)
+	verify {
+		assert_eq!(
+			Pallet::<T>::accounts_payable(&reward_account).unwrap().contributed_relay_addresses.len() as u32,
+			x - splits_len
+		);
+		for (_, new_account) in splits {
+			assert!(Pallet::<T>::accounts_payable(&new_account).is_some());
+		}
+	}
+
+	set_init_vesting_block {
+	}:  _(RawOrigin::Root, 1u32.into())
+	verify {
+		assert_eq!(Pallet::<T>::init_vesting_block(), 1u32.into());
+	}
+
+	fund_pot {
+		// The account funding the pot
+		let source: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+		let history = Pallet::<T>::funding_history_count();
+	}:  _(RawOrigin::Root, source, 100u32.into())
+	verify {
+		assert_eq!(Pallet::<T>::funding_history_count(), history + 1);
+	}
+
+	withdraw_excess {
+		// Fund pallet account, nothing is owed to contributors
+		let total_pot = 200u32;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// The account receiving the excess funds
+		let dest: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+		let history = Pallet::<T>::funding_history_count();
+	}:  _(RawOrigin::Root, dest, 100u32.into())
+	verify {
+		assert_eq!(Pallet::<T>::funding_history_count(), history + 1);
+	}
+
+	reset_campaign {
+		let x in 1..max_batch_contributors::<T>();
+
+		// Fund pallet account
+		let total_pot = 100u32*x;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// Create x contributors that received their initial payment
+		let contributors = create_contributors::<T>(x, 0);
+
+		// Insert them
+		insert_contributors::<T>(contributors)?;
+
+	}:  _(RawOrigin::Root, x)
+	verify {
+		assert_eq!(Pallet::<T>::total_contributors(), 0);
+		assert_eq!(Pallet::<T>::pot(), 0u32.into());
+	}
+
+	open_supplemental_round {
+		// Fund pallet account
+		let total_pot = 100u32;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// 1 contributor is enough
+		let contributors = create_contributors::<T>(1, 0);
+		insert_contributors::<T>(contributors)?;
+		close_initialization::<T>(10u32.into())?;
+
+		// Funds for the round
+		T::RewardCurrency::deposit_creating(&Pallet::<T>::account_id(), 100u32.into());
+		let funds = Pallet::<T>::excess_funds();
+	}:  _(RawOrigin::Root, funds, Some((20u32.into(), 30u32.into())))
+	verify {
+		assert!(Pallet::<T>::supplemental_round().is_some());
+	}
+
+	complete_supplemental_round {
+		// Fund pallet account
+		let total_pot = 100u32;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// 1 contributor is enough
+		let contributors = create_contributors::<T>(1, 0);
+		insert_contributors::<T>(contributors)?;
+		close_initialization::<T>(10u32.into())?;
+
+		// Open the round with the funds of a late contributor
+		T::RewardCurrency::deposit_creating(&Pallet::<T>::account_id(), 100u32.into());
+		let funds = Pallet::<T>::excess_funds();
+		Pallet::<T>::open_supplemental_round(RawOrigin::Root.into(), funds, None)?;
+		let late_contributor = create_funded_user::<T>("user", SEED, 0u32.into());
+		Pallet::<T>::initialize_reward_vec(
+			RawOrigin::Root.into(),
+			vec![(AccountId32::from([1u8;32]).into(), Some(late_contributor), funds)],
+		)?;
+	}:  _(RawOrigin::Root)
+	verify {
+		assert!(Pallet::<T>::supplemental_round().is_none());
+	}
+
+	close_supplemental_round {
+		// Fund pallet account
+		let total_pot = 100u32;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// 1 contributor is enough
+		let contributors = create_contributors::<T>(1, 0);
+		insert_contributors::<T>(contributors)?;
+		close_initialization::<T>(10u32.into())?;
+
+		// Open a round that gets no contributors
+		T::RewardCurrency::deposit_creating(&Pallet::<T>::account_id(), 100u32.into());
+		let funds = Pallet::<T>::excess_funds();
+		Pallet::<T>::open_supplemental_round(RawOrigin::Root.into(), funds, None)?;
+	}:  _(RawOrigin::Root)
+	verify {
+		assert!(Pallet::<T>::supplemental_round().is_none());
+		assert_eq!(Pallet::<T>::excess_funds(), funds);
+	}
+
+	ingest_relay_contributions {
+		let x in 1..max_batch_contributors::<T>();
+		// Bytes of the proof that are not needed to read the contributions
+		let y in 0..MAX_EXTRA_PROOF_BYTES;
+
+		let contribution: u128 = default_balance::<T>().unique_saturated_into();
+		let reward = T::RewardConversion::convert(contribution);
+		fund_specific_account::<T>(Pallet::<T>::account_id(), reward * BalanceOf::<T>::from(x));
+
+		// Prove x contributions to the fund
+		let contributions: Vec<(AccountId32, u128)> = create_contributors::<T>(x, 0)
+			.into_iter()
+			.map(|(relay_account, _, _)| (relay_account.into(), contribution))
+			.collect();
+		let (root, mut proof) =
+			build_relay_state_proof(T::CrowdloanFundIndex::get(), &contributions);
+		proof.push(vec![0u8; y as usize]);
+		T::RelayStateRoot::set_relay_state_root(root);
+		Pallet::<T>::snapshot_relay_state_root(RawOrigin::Root.into())?;
+
+		let relay_accounts: Vec<T::RelayChainAccountId> = contributions
+			.into_iter()
+			.map(|(relay_account, _)| relay_account.into())
+			.collect();
+		let caller: T::AccountId = create_funded_user::<T>("user", SEED, 0u32.into());
+	}:  _(RawOrigin::Signed(caller), relay_accounts, proof)
+	verify {
+		assert_eq!(Pallet::<T>::total_contributors(), x);
+	}
+
+	initialize_reward_entries {
+		let x in 1..max_batch_contributors::<T>();
+		let y = MAX_ALREADY_USERS;
+
+		let total_pot = 100u32*(x+y);
+		// Every new contributor is referred, which is paid from the pot as well
+		let referral_rewards = Pallet::<T>::referral_reward(100u32.into()) * x.into();
+		// Fund pallet account
+		fund_specific_account::<T>(Pallet::<T>::account_id(), referral_rewards + total_pot.into());
+		// Fund the bonus pot for the early-bird bonuses
+		fund_specific_account::<T>(Pallet::<T>::bonus_account_id(), total_pot.into());
+
+		// Create y contributors
+		let contributors = create_contributors::<T>(y, 0);
+		let referrer = contributors[0].1.clone().unwrap();
+
+		// Insert them
+		insert_contributors::<T>(contributors)?;
+
+		// This X new contributors are the ones we will count
+		let new_entries: Vec<InitializationEntryOf<T>> = create_contributors::<T>(x, y)
+			.into_iter()
+			.map(|(relay_account, native_account, reward)| InitializationEntry {
+				relay_account,
+				native_account,
+				reward,
+				early_bird_bonus: Perbill::from_percent(10),
+				referrer: Some(Referrer::Native(referrer.clone())),
+			})
+			.collect();
+
+		let verifier = create_funded_user::<T>("user", SEED, 0u32.into());
+
+	}:  _(RawOrigin::Root, new_entries)
+	verify {
+		assert!(Pallet::<T>::accounts_payable(&verifier).unwrap().bonus.early_bird > 0u32.into());
+	}
+
+	fund_bonus_pot {
+		let source = create_funded_user::<T>("user", SEED, 100u32.into());
+	}:  _(RawOrigin::Root, source, 100u32.into())
+	verify {
+		assert_eq!(Pallet::<T>::bonus_pot(), 100u32.into());
+	}
+
+	exit_early {
+		// Fund pallet account
+		let total_pot = 100u32;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// The user that will make the call
+		let caller: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+
+		// Create 1 contributor
+		let contributors: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)> =
+			vec![(AccountId32::from([1u8;32]).into(), Some(caller.clone()), total_pot.into())];
+
+		// Insert them
+		insert_contributors::<T>(contributors)?;
+
+		// Close initialization
+		close_initialization::<T>(10u32.into())?;
+
+		// First inherent
+		T::VestingBlockProvider::set_block_number(1u32.into());
+		Pallet::<T>::on_finalize(BlockNumberFor::<T>::one());
+
+		// Create 4th relay block, by now part of the reward is still unvested
+		T::VestingBlockProvider::set_block_number(4u32.into());
+	}:  _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Pallet::<T>::is_fully_claimed(&Pallet::<T>::accounts_payable(&caller).unwrap()));
+	}
+
+	redistribute_excess {
+		// Fund pallet account
+		let total_pot = 100u32;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// Create 1 contributor to redistribute to
+		let caller: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+		let contributors: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)> =
+			vec![(AccountId32::from([1u8;32]).into(), Some(caller.clone()), total_pot.into())];
+		insert_contributors::<T>(contributors)?;
+
+		// Close initialization
+		close_initialization::<T>(10u32.into())?;
+
+		// Add funds beyond the outstanding rewards
+		let source = create_funded_user::<T>("source", SEED, 100u32.into());
+		Pallet::<T>::fund_pot(RawOrigin::Root.into(), source, 100u32.into())?;
+	}:  _(RawOrigin::Root, 100u32.into())
+	verify {
+		assert!(Pallet::<T>::redistribution_index() > Default::default());
+	}
+
+	sweep_unassociated {
+		let x in 1..max_batch_contributors::<T>();
+
+		// Fund pallet account
+		let total_pot = 100u32*(x+1);
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// 1 associated contributor to redistribute to, and x unassociated ones to sweep
+		let caller: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+		let relay_accounts: Vec<T::RelayChainAccountId> = create_contributors::<T>(x, 1)
+			.into_iter()
+			.map(|(relay_account, _, _)| relay_account)
+			.collect();
+		let mut contributors: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)> =
+			relay_accounts
+				.iter()
+				.map(|relay_account| (relay_account.clone(), None, 100u32.into()))
+				.collect();
+		contributors.push((AccountId32::from([1u8;32]).into(), Some(caller.clone()), 100u32.into()));
+		insert_contributors::<T>(contributors)?;
+
+		// Close initialization
+		close_initialization::<T>(10u32.into())?;
+
+		// The vesting period is over
+		T::VestingBlockProvider::set_block_number(10u32.into());
+	}:  _(RawOrigin::Root, relay_accounts.clone())
+	verify {
+		assert!(Pallet::<T>::unassociated_contributions(&relay_accounts[0]).is_none());
+		assert!(Pallet::<T>::redistribution_index() > Default::default());
+	}
+
+	snapshot_relay_state_root {
+		let (root, _) = build_relay_state_proof(T::CrowdloanFundIndex::get(), &[]);
+		T::RelayStateRoot::set_relay_state_root(root);
+	}:  _(RawOrigin::Root)
+	verify {
+		assert_eq!(Pallet::<T>::relay_state_root_snapshot(), Some(root));
+	}
+
+	set_reward_curve {
+		let x in 0..MAX_REWARD_CURVE_TIERS;
+
+		// Thresholds increasing by 100 with rates diminishing by 5%
+		let tiers: Vec<(BalanceOf<T>, Perbill)> = (0..x)
+			.map(|i| ((100u32 * (i + 1)).into(), Perbill::from_percent(100 - 5 * i)))
+			.collect();
+		let curve = RewardCurveInfo { tiers, cap: Some(1_000_000u32.into()) };
+	}:  _(RawOrigin::Root, Some(curve))
+	verify {
+		assert!(Pallet::<T>::reward_curve().is_some());
+	}
+
+	initialize_contributions {
+		let x in 1..max_batch_contributors::<T>();
+		let y = MAX_ALREADY_USERS;
+
+		let contribution: u128 = default_balance::<T>().unique_saturated_into();
+		let reward = T::RewardConversion::convert(contribution);
+		fund_specific_account::<T>(
+			Pallet::<T>::account_id(),
+			BalanceOf::<T>::from(100u32 * y) + reward * BalanceOf::<T>::from(x),
+		);
+
+		// Create y contributors
+		let contributors = create_contributors::<T>(y, 0);
+
+		// Insert them
+		insert_contributors::<T>(contributors)?;
+
+		// This X new contributions are the ones we will count
+		let new_contributions: Vec<(T::RelayChainAccountId, Option<T::AccountId>, u128)> =
+			create_contributors::<T>(x, y)
+				.into_iter()
+				.map(|(relay_account, native_account, _)| (relay_account, native_account, contribution))
+				.collect();
+
+		let verifier = create_funded_user::<T>("user", SEED, 0u32.into());
+
+	}:  _(RawOrigin::Root, new_contributions)
+	verify {
+		assert!(Pallet::<T>::accounts_payable(&verifier).is_some());
+	}
+
+	approve_merge {
+		// Fund pallet account
+		let total_pot = 200u32;
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// The user that will make the call
+		let caller: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+
+		// The account the caller wants to merge with
+		let other_account: T::AccountId = create_funded_user::<T>("user", SEED-1, 100u32.into());
+
+		// Create 2 contributors
+		let contributors: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)> = vec![
+			(AccountId32::from([1u8;32]).into(), Some(caller.clone()), 100u32.into()),
+			(AccountId32::from([2u8;32]).into(), Some(other_account.clone()), 100u32.into()),
+		];
+
+		// Insert them
+		insert_contributors::<T>(contributors)?;
+
+		// Close initialization
+		close_initialization::<T>(10u32.into())?;
+
+	}:  _(RawOrigin::Signed(caller.clone()), Some(other_account.clone()))
+	verify {
+		assert_eq!(Pallet::<T>::merge_approvals(&caller), Some(other_account));
+	}
+
+	merge_reward_entries {
+		// The weight will depend on the number of proofs provided for each of the entries
+		let x in 1..max_batch_contributors::<T>();
+		let y in 1..max_batch_contributors::<T>();
+
+		// Fund pallet account
+		let total_pot = 100u32*(x+y);
+		fund_specific_account::<T>(Pallet::<T>::account_id(), total_pot.into());
+
+		// The reward account that will be merged
+		let from: T::AccountId = create_funded_user::<T>("user", SEED, 100u32.into());
+
+		// The reward account receiving the merge
+		let into: T::AccountId = create_funded_user::<T>("user", SEED-1, 100u32.into());
+
+		// Construct payload
+		let payload = Pallet::<T>::merge_reward_entries_payload(&from, &into);
+
+		// Create x sigs for the entry of from and y sigs for the entry of into
+		let mut from_proofs: Vec<(T::RelayChainAccountId, MultiSignature)> = Vec::new();
+		for i in 0..x {
+			let (relay_account, signature) = create_sig::<T>(SEED-i, payload.clone());
+			from_proofs.push((relay_account.into(), signature));
+		}
+		let mut into_proofs: Vec<(T::RelayChainAccountId, MultiSignature)> = Vec::new();
+		for i in 0..y {
+			let (relay_account, signature) = create_sig::<T>(SEED-x-i, payload.clone());
+			into_proofs.push((relay_account.into(), signature));
+		}
+
+		let mut contributors: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)> = Vec::new();
+		for (relay_account, _) in from_proofs.clone() {
+			contributors.push((relay_account, Some(from.clone()), 100u32.into()));
+		}
+		for (relay_account, _) in into_proofs.clone() {
+			contributors.push((relay_account, Some(into.clone()), 100u32.into()));
+		}
+
+		// Insert them
+		insert_contributors::<T>(contributors)?;
+
+		// Close initialization
+		close_initialization::<T>(10u32.into())?;
+
+		// The caller owns none of the entries, so all the proofs need to be verified
+		let caller: T::AccountId = create_funded_user::<T>("caller", SEED, 100u32.into());
+
+	}:  _(RawOrigin::Signed(caller), from.clone(), into.clone(), from_proofs, into_proofs)
+	verify {
+		assert!(Pallet::<T>::accounts_payable(&from).is_none());
+		assert_eq!(Pallet::<T>::accounts_payable(&into).unwrap().total_reward, (100u32*(x+y)).into());
+	}
+
 }
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,158 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helper implementations to configure the crowdloan rewards pallet
+
+use crate::{
+	traits::{EarlyExitPenalty, RewardConversion},
+	Config, NegativeImbalanceOf, Pallet,
+};
+use frame_support::traits::{Currency, Get, Imbalance, OnUnbalanced};
+use sp_runtime::{
+	traits::{Saturating, UniqueSaturatedFrom, Zero},
+	FixedPointNumber, FixedU128, Perbill, Perquintill,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Deposits the funds handed over by the pallet into a fixed account, eg the treasury
+pub struct ResolveToAccount<T, A>(PhantomData<(T, A)>);
+
+impl<T: Config, A: Get<T::AccountId>> OnUnbalanced<NegativeImbalanceOf<T>>
+	for ResolveToAccount<T, A>
+{
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T>) {
+		T::RewardCurrency::resolve_creating(&A::get(), amount);
+	}
+}
+
+/// Puts the funds handed over by the pallet back into the pot, and redistributes them among the
+/// entries not fully claimed. Without such entries, the funds are kept as excess funds in the pot
+pub struct RedistributeToVesters<T>(PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<NegativeImbalanceOf<T>> for RedistributeToVesters<T> {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T>) {
+		let value = amount.peek();
+		T::RewardCurrency::resolve_creating(&Pallet::<T>::account_id(), amount);
+		Pallet::<T>::redistribute(value);
+	}
+}
+
+/// Pays a fixed number of reward tokens per contributed relay token
+pub struct FixedRatio<Ratio>(PhantomData<Ratio>);
+
+impl<Balance: UniqueSaturatedFrom<u128>, Ratio: Get<FixedU128>> RewardConversion<Balance>
+	for FixedRatio<Ratio>
+{
+	fn convert(contribution: u128) -> Balance {
+		Balance::unique_saturated_from(Ratio::get().saturating_mul_int(contribution))
+	}
+}
+
+/// Pays a fixed number of reward tokens per contributed relay token, where both amounts are
+/// expressed in whole tokens and the relay and native tokens have a different number of decimals
+pub struct DecimalAdjustedRatio<Ratio, RelayDecimals, NativeDecimals>(
+	PhantomData<(Ratio, RelayDecimals, NativeDecimals)>,
+);
+
+impl<Balance, Ratio, RelayDecimals, NativeDecimals> RewardConversion<Balance>
+	for DecimalAdjustedRatio<Ratio, RelayDecimals, NativeDecimals>
+where
+	Balance: UniqueSaturatedFrom<u128>,
+	Ratio: Get<FixedU128>,
+	RelayDecimals: Get<u32>,
+	NativeDecimals: Get<u32>,
+{
+	fn convert(contribution: u128) -> Balance {
+		let (relay_decimals, native_decimals) = (RelayDecimals::get(), NativeDecimals::get());
+		let reward = if native_decimals >= relay_decimals {
+			let scale = 10u128.saturating_pow(native_decimals - relay_decimals);
+			Ratio::get().saturating_mul_int(contribution.saturating_mul(scale))
+		} else {
+			let scale = 10u128.saturating_pow(relay_decimals - native_decimals);
+			Ratio::get().saturating_mul_int(contribution) / scale
+		};
+		Balance::unique_saturated_from(reward)
+	}
+}
+
+/// Limits the reward given by another conversion to `Cap`
+pub struct Capped<Conversion, Cap>(PhantomData<(Conversion, Cap)>);
+
+impl<Balance, Conversion, Cap> RewardConversion<Balance> for Capped<Conversion, Cap>
+where
+	Balance: Ord,
+	Conversion: RewardConversion<Balance>,
+	Cap: Get<Balance>,
+{
+	fn convert(contribution: u128) -> Balance {
+		Conversion::convert(contribution).min(Cap::get())
+	}
+}
+
+/// Pays a decreasing number of reward tokens per contributed relay token as contributions grow,
+/// to disincentivize whales
+///
+/// `Tiers` is a list of `(threshold, ratio)` sorted by threshold, starting at 0. The part of a
+/// contribution between a threshold and the next one is paid with the ratio of the threshold.
+pub struct Progressive<Tiers>(PhantomData<Tiers>);
+
+impl<Balance: UniqueSaturatedFrom<u128>, Tiers: Get<Vec<(u128, FixedU128)>>>
+	RewardConversion<Balance> for Progressive<Tiers>
+{
+	fn convert(contribution: u128) -> Balance {
+		let reward = apply_tiers(contribution, &Tiers::get(), |ratio, part| {
+			ratio.saturating_mul_int(part)
+		});
+		Balance::unique_saturated_from(reward)
+	}
+}
+
+/// Walks `tiers` of `(threshold, rate)` sorted by threshold, applying the rate of each threshold
+/// with `apply` to the part of `amount` between the threshold and the next one. The part of
+/// `amount` below the first threshold is left out
+///
+/// Shared by `Progressive` and the on-chain reward curve
+pub fn apply_tiers<Amount, Rate>(
+	amount: Amount,
+	tiers: &[(Amount, Rate)],
+	apply: impl Fn(&Rate, Amount) -> Amount,
+) -> Amount
+where
+	Amount: Copy + Ord + Saturating + Zero,
+{
+	let mut total = Amount::zero();
+	for (index, (threshold, rate)) in tiers.iter().enumerate() {
+		if amount <= *threshold {
+			break;
+		}
+		let tier_end = tiers
+			.get(index + 1)
+			.map_or(amount, |(next_threshold, _)| amount.min(*next_threshold));
+		total = total.saturating_add(apply(rate, tier_end.saturating_sub(*threshold)));
+	}
+	total
+}
+
+/// Forfeits `Initial` of the unvested reward at the vesting start, decreasing linearly down to
+/// nothing at the vesting end
+pub struct LinearPenalty<Initial>(PhantomData<Initial>);
+
+impl<Initial: Get<Perbill>> EarlyExitPenalty for LinearPenalty<Initial> {
+	fn penalty(elapsed: Perquintill) -> Perbill {
+		let remaining = Perquintill::one().saturating_sub(elapsed);
+		Perbill::from_parts((remaining * u64::from(Initial::get().deconstruct())) as u32)
+	}
+}
@@ -22,14 +22,20 @@
 //! ## Monetary Policy
 //!
 //! This is simple and mock for now. We can do whatever we want.
-//! This pallet stores a constant  "reward ratio" which is the number of reward tokens to pay per
-//! contributed token. In simple cases this can be 1, but needs to be customizeable to allow for
-//! vastly differing absolute token supplies between relay and para.
+//! Rewards are either given directly to initialize_reward_vec, or computed from the contributed
+//! relay tokens with the `RewardConversion` policy. In simple cases this is a fixed "reward ratio"
+//! of reward tokens to pay per contributed token, but it needs to be customizeable to allow for
+//! vastly differing absolute token supplies between relay and para. The `impls` module provides a
+//! fixed ratio, a ratio adjusted to the decimals of both tokens, a cap and a progressive curve.
 //! Vesting is also linear. No tokens are vested at genesis and they unlock linearly until a
 //! predecided block number. Vesting computations happen on demand when payouts are requested. So
 //! no block weight is ever wasted on this, and there is no "base-line" cost of updating vestings.
+//! If `PerEntryVesting` is set, the same vesting period starts for each reward entry when it gets
+//! associated with a native account instead.
 //! Like I said, we can anything we want there. Even a non-linear reward curve to disincentivize
-//! whales.
+//! whales, like `impls::Progressive`. Root can also set a `RewardCurve` on chain with
+//! set_reward_curve. It applies diminishing marginal rates above its thresholds and a cap per relay
+//! account to every reward being initialized, and reports the reduction in the events.
 //!
 //! ## Payout Mechanism
 //!
@@ -38,6 +44,39 @@
 //! wait and claim the entire thing once it is fully vested. We could consider auto payouts if we
 //! want.
 //!
+//! Entries initialized with initialize_reward_entries can carry an early-bird bonus on top of
+//! their reward, paid along with the reward as it is claimed. Entries that claimed no more than
+//! `LoyaltyThreshold` of their reward beyond the initial payment before vesting end get a
+//! `LoyaltyBonus` in their first claim after it. Bonuses are paid from a separate bonus pot,
+//! funded with fund_bonus_pot, and accounted in InitializedBonusAmount and PaidBonusAmount.
+//!
+//! These entries can also name the referrer of the contributor, which is credited with
+//! `ReferralReward` out of the pot. Unlike bonuses, referral rewards are added to the reward entry
+//! of the referrer and vest along with the rest of it.
+//!
+//! Contributors not willing to wait for the vesting end can call exit_early, which pays their
+//! vested reward along with part of the unvested one and closes their entry. The forfeited part
+//! is given by `EarlyExitPenalty`, decreasing as the vesting period elapses, and handed over to
+//! `PenaltyDestination`.
+//!
+//! Funds can be redistributed among the associated entries not fully claimed yet, pro rata of
+//! their total reward, either by root with redistribute_excess or by using RedistributeToVesters
+//! as `PenaltyDestination`. The rewards of entries still unassociated at the end of their
+//! vesting period are redistributed as well when root sweeps them with sweep_unassociated, and
+//! their relay accounts cannot be initialized again. The redistributed rewards per share are
+//! accumulated in RedistributionIndex, so that the share of each entry is computed lazily and
+//! paid in full along with its next claim.
+//!
+//! Payouts that would leave the native account below the existential deposit are not made.
+//! Skipped initial payments and deferred claims stay claimable, accumulating with the vested
+//! reward until they can be paid.
+//!
+//! Contributors often associate an empty native account, so claim is fee-free when there is a
+//! non-zero vested payout, at most once every `FeelessClaimInterval` blocks. So is
+//! associate_native_identity when it would succeed and make a non-zero initial payment, which can
+//! only happen once per relay account. This needs the runtime to wrap its payment extension in
+//! `SkipCheckIfFeeless`.
+//!
 //! ## Sourcing Contribution Information
 //!
 //! The pallet can learn about the crowdloan contributions in several ways.
@@ -46,17 +85,23 @@
 //!
 //! The simplest way is to call the initialize_reward_vec through a democracy proposal/sudo call.
 //! This makes sense in a scenario where the crowdloan took place entirely offchain.
-//! This extrinsic initializes the associated and unassociated stoerage with the provided data
+//! This extrinsic initializes the associated and unassociated stoerage with the provided data.
+//! initialize_contributions does the same with the raw contributions instead of the rewards,
+//! converting them with `RewardConversion` and keeping them in the reward entries for auditing.
+//! Entries that cannot be initialized, because they are duplicates, are below `MinimumReward` or
+//! their initial payment fails, are skipped without failing the batch. Only the other entries need
+//! to fit in the funds, so a batch can be sent again once some of its entries were initialized.
+//! Each batch ends with an InitializationBatchProcessed event summarizing what was done.
 //!
 //! * **ReadingRelayState**
 //!
-//! The most elegant, but most complex solution would be for the para to read the contributions
-//! directly from the relay state. Blocked by https://github.com/paritytech/cumulus/issues/320 so
-//! I won't pursue it further right now. I can't decide whether that would really add security /
-//! trustlessness, or is just a sexy blockchain thing to do. Contributors can always audit the
-//! democracy proposal and make sure their contribution is in it, so in that sense reading relay state
-//! isn't necessary. But if a single contribution is left out, the rest of the contributors might
-//! not care enough to delay network launch. The little guy might get censored.
+//! The most elegant solution is for the para to read the contributions directly from the relay
+//! state. Anyone can call ingest_relay_contributions with a storage proof of the contributions
+//! kept by the relay crowdloan pallet for the `CrowdloanFundIndex` fund. The proof is verified
+//! against the relay state root pinned by governance with snapshot_relay_state_root, eg at the end
+//! of the crowdloan, and each proven contribution is turned into a reward with
+//! `RewardConversion`. This way nobody has to trust a governance-provided list: if a single
+//! contribution is left out, the little guy can ingest it themselves.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -66,41 +111,67 @@ pub use pallet::*;
 
 #[cfg(any(test, feature = "runtime-benchmarks"))]
 mod benchmarks;
+pub mod impls;
+pub mod migrations;
 #[cfg(test)]
 pub(crate) mod mock;
+pub mod relay_state;
+pub mod runtime_api;
 #[cfg(test)]
 mod tests;
+pub mod traits;
 pub mod weights;
 
 #[pallet]
 pub mod pallet {
 	use super::*;
+	use crate::relay_state::RelayStateProof;
+	use crate::traits::{
+		EarlyExitPenalty, OnRewardEvent, RelayStateRootProvider, RewardConversion, StakingAdapter,
+	};
 	use frame_support::traits::WithdrawReasons;
 	use frame_support::{
 		pallet_prelude::*,
-		traits::{Currency, ExistenceRequirement::AllowDeath},
+		storage::with_storage_layer,
+		traits::{
+			Currency,
+			ExistenceRequirement::{AllowDeath, KeepAlive},
+			OnUnbalanced,
+		},
 		PalletId,
 	};
 	use frame_system::pallet_prelude::*;
-	use sp_core::crypto::AccountId32;
+	use parity_scale_codec::DecodeWithMemTracking;
+	use sp_core::{crypto::AccountId32, H256};
 	use sp_runtime::traits::{
-		AccountIdConversion, AtLeast32BitUnsigned, BlockNumberProvider, Saturating, Verify,
+		AccountIdConversion, AtLeast32BitUnsigned, BlockNumberProvider, CheckedSub, Saturating,
+		UniqueSaturatedFrom, UniqueSaturatedInto, Verify, Zero,
 	};
-	use sp_runtime::{MultiSignature, Perbill};
-	use sp_std::collections::btree_map::BTreeMap;
+	use sp_runtime::{FixedPointNumber, FixedU128, MultiSignature, Perbill, Perquintill};
+	use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
 	use sp_std::vec;
 	use sp_std::vec::Vec;
 	#[pallet::pallet]
 	#[pallet::without_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	// The crowdloan rewards pallet
 	pub struct Pallet<T>(PhantomData<T>);
 
+	/// The in-code storage version.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	pub const PALLET_ID: PalletId = PalletId(*b"Crowdloa");
 
 	// The wrapper around which the reward changing message needs to be wrapped
 	pub const WRAPPED_BYTES_PREFIX: &[u8] = b"<Bytes>";
 	pub const WRAPPED_BYTES_POSTFIX: &[u8] = b"</Bytes>";
 
+	/// Maximum number of reward entries returned in a single page by the runtime API
+	pub const MAX_PAGE_SIZE: u32 = 1000;
+
+	/// Maximum number of tiers of the reward curve
+	pub const MAX_REWARD_CURVE_TIERS: u32 = 16;
+
 	/// Configuration trait of this pallet.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
@@ -127,7 +198,8 @@ pub mod pallet {
 			//TODO these AccountId32 bounds feel a little extraneous. I wonder if we can remove them.
 			+ Into<AccountId32>
 			+ From<AccountId32>
-			+ Ord;
+			+ Ord
+			+ MaybeSerializeDeserialize;
 
 		// The origin that is allowed to change the reward address with relay signatures
 		type RewardAddressChangeOrigin: EnsureOrigin<Self::RuntimeOrigin>;
@@ -141,12 +213,83 @@ pub mod pallet {
 		type RewardAddressAssociateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
 		/// The type that will be used to track vesting progress
-		type VestingBlockNumber: AtLeast32BitUnsigned + Parameter + Default + Into<BalanceOf<Self>>;
+		type VestingBlockNumber: AtLeast32BitUnsigned
+			+ Parameter
+			+ Default
+			+ Into<BalanceOf<Self>>
+			+ MaybeSerializeDeserialize;
 
 		/// The notion of time that will be used for vesting. Probably
 		/// either the relay chain or sovereign chain block number.
 		type VestingBlockProvider: BlockNumberProvider<BlockNumber = Self::VestingBlockNumber>;
 
+		/// Whether vesting is measured from the vesting start of each reward entry (set when
+		/// the entry gets associated with a native account) instead of the global InitVestingBlock
+		#[pallet::constant]
+		type PerEntryVesting: Get<bool>;
+
+		/// Whether InitVestingBlock is set in the first block of the chain, unless it was already
+		/// set explicitly. Disable this when adding the pallet to a running chain through a runtime
+		/// upgrade, and set the vesting start in complete_initialization or set_init_vesting_block
+		#[pallet::constant]
+		type InitVestingAtFirstBlock: Get<bool>;
+
+		/// Handler for the difference between the pot and the initialized rewards when completing
+		/// the initialization. Use () to burn it, or eg the treasury to keep it in circulation
+		type InitializationDust: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Account receiving the remaining pot when the campaign is reset
+		type ResetPotDestination: Get<Self::AccountId>;
+
+		/// Hooks called when rewards are paid, identities associated or reward addresses updated
+		type OnRewardEvent: OnRewardEvent<
+			Self::AccountId,
+			Self::RelayChainAccountId,
+			BalanceOf<Self>,
+		>;
+
+		/// Where claim_and_stake stakes the claimed rewards, eg a delegation or a pool bond
+		type StakingAdapter: StakingAdapter<Self::AccountId, BalanceOf<Self>>;
+
+		/// The relay state root pinned by snapshot_relay_state_root, which proofs given to
+		/// ingest_relay_contributions are verified against
+		type RelayStateRoot: RelayStateRootProvider;
+
+		/// Index of the relay crowdloan fund whose contributions can be ingested
+		#[pallet::constant]
+		type CrowdloanFundIndex: Get<u32>;
+
+		/// Converts relay contributions into rewards, for initialize_contributions and
+		/// ingest_relay_contributions
+		type RewardConversion: RewardConversion<BalanceOf<Self>>;
+
+		/// Loyalty bonus paid from the bonus pot at vesting end, as a fraction of the total reward
+		#[pallet::constant]
+		type LoyaltyBonus: Get<Perbill>;
+
+		/// Fraction of the vesting reward, ie the reward beyond the initial payment, an entry can
+		/// claim before vesting end and still get the loyalty bonus
+		#[pallet::constant]
+		type LoyaltyThreshold: Get<Perbill>;
+
+		/// Reward credited to the referrer of every contributor initialized with a referrer
+		#[pallet::constant]
+		type ReferralReward: Get<ReferralRewardKind<BalanceOf<Self>>>;
+
+		/// The fraction of the unvested reward forfeited by exit_early, depending on how much of
+		/// the vesting period has elapsed
+		type EarlyExitPenalty: EarlyExitPenalty;
+
+		/// Handler for the rewards forfeited by exit_early. Use () to burn them, eg the treasury
+		/// to keep them in circulation, or RedistributeToVesters to share them among the entries
+		/// still vesting
+		type PenaltyDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Minimum number of blocks between two fee-free claims of the same account. Claims made
+		/// sooner pay the usual fees
+		#[pallet::constant]
+		type FeelessClaimInterval: Get<BlockNumberFor<Self>>;
+
 		type WeightInfo: WeightInfo;
 	}
 
@@ -154,6 +297,15 @@ pub mod pallet {
 		<T as frame_system::Config>::AccountId,
 	>>::Balance;
 
+	pub type StakingTargetOf<T> = <<T as Config>::StakingAdapter as StakingAdapter<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+	>>::Target;
+
+	pub type NegativeImbalanceOf<T> = <<T as Config>::RewardCurrency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
+
 	/// Stores info about the rewards owed as well as how much has been vested so far.
 	/// For a primer on this kind of design, see the recipe on compounding interest
 	/// https://substrate.dev/recipes/fixed-point.html#continuously-compounding
@@ -163,6 +315,218 @@ pub mod pallet {
 		pub total_reward: BalanceOf<T>,
 		pub claimed_reward: BalanceOf<T>,
 		pub contributed_relay_addresses: Vec<T::RelayChainAccountId>,
+		/// Block at which the entry was associated with a native account. Vesting starts here
+		/// instead of at InitVestingBlock if PerEntryVesting is set
+		pub vesting_start: Option<T::VestingBlockNumber>,
+		/// Block at which the entry is fully vested, for entries of a supplemental round with
+		/// its own vesting window. The entry vests from vesting_start (or InitVestingBlock) to here
+		pub vesting_end: Option<T::VestingBlockNumber>,
+		/// Relay tokens contributed by the relay accounts of the entry, if the reward was
+		/// converted from them. Kept for auditing
+		pub contribution: Option<u128>,
+		/// Bonuses of the entry, paid from the bonus pot
+		pub bonus: BonusInfo<BalanceOf<T>>,
+		/// Share of the entry in the redistributed rewards
+		pub redistribution: RedistributionInfo<BalanceOf<T>>,
+	}
+
+	/// Share of a reward entry in the redistributed rewards, settled lazily against
+	/// RedistributionIndex like in the recipe referenced on RewardInfo
+	#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	pub struct RedistributionInfo<Balance> {
+		/// RedistributionIndex when the share of the entry was last settled
+		pub index: FixedU128,
+		/// Redistributed rewards owed to the entry and not paid yet
+		pub pending: Balance,
+	}
+
+	/// Bonuses of a reward entry, paid from the bonus pot on top of the reward
+	#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	pub struct BonusInfo<Balance> {
+		/// Early-bird bonus of the entry, paid along with the reward as it is claimed
+		pub early_bird: Balance,
+		/// Early-bird bonus paid so far
+		pub early_bird_paid: Balance,
+		/// Whether the loyalty bonus was already decided, in the first claim after vesting end
+		pub loyalty_settled: bool,
+	}
+
+	/// A reward entry to initialize with initialize_reward_entries
+	#[derive(
+		Clone,
+		Encode,
+		Decode,
+		DecodeWithMemTracking,
+		RuntimeDebug,
+		PartialEq,
+		Eq,
+		scale_info::TypeInfo,
+	)]
+	pub struct InitializationEntry<RelayChainAccountId, AccountId, Balance> {
+		pub relay_account: RelayChainAccountId,
+		pub native_account: Option<AccountId>,
+		pub reward: Balance,
+		/// Early-bird bonus on top of the reward, eg 10% for a 1.1x multiplier
+		pub early_bird_bonus: Perbill,
+		/// Who referred the contributor, credited with ReferralReward
+		pub referrer: Option<Referrer<RelayChainAccountId, AccountId>>,
+	}
+
+	/// Who referred a contributor. The referrer needs to have a reward entry already
+	#[derive(
+		Clone,
+		Encode,
+		Decode,
+		DecodeWithMemTracking,
+		RuntimeDebug,
+		PartialEq,
+		Eq,
+		scale_info::TypeInfo,
+	)]
+	pub enum Referrer<RelayChainAccountId, AccountId> {
+		/// A native account with an entry in AccountsPayable
+		Native(AccountId),
+		/// A relay account with an entry in UnassociatedContributions
+		Relay(RelayChainAccountId),
+	}
+
+	pub type ReferrerOf<T> =
+		Referrer<<T as Config>::RelayChainAccountId, <T as frame_system::Config>::AccountId>;
+
+	/// The reward of a referrer for each contributor it referred
+	#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo)]
+	pub enum ReferralRewardKind<Balance> {
+		/// A fraction of the reward of the contributor
+		Percentage(Perbill),
+		/// The same amount for every contributor
+		Flat(Balance),
+	}
+
+	pub type InitializationEntryOf<T> = InitializationEntry<
+		<T as Config>::RelayChainAccountId,
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+	>;
+
+	/// Aggregate counters of the rewards, kept up to date by every call
+	#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	pub struct RewardStatistics<Balance> {
+		/// Rewards paid to contributors, including the initial payments and the redistributed
+		/// rewards paid along with the claims. Bonuses are paid from the bonus pot and left out
+		pub total_claimed: Balance,
+		/// Rewards of the entries associated with a native account
+		pub associated_rewards: Balance,
+		/// Rewards of the entries not associated with a native account yet
+		pub unassociated_rewards: Balance,
+		/// Number of relay accounts associated with a native account
+		pub associated_relay_accounts: u32,
+		/// Number of native accounts that claimed their whole reward
+		pub fully_claimed_accounts: u32,
+	}
+
+	/// When and how many times an account claimed its rewards
+	#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	pub struct ClaimRecord<BlockNumber> {
+		/// Block of the last claim
+		pub last_claim_block: BlockNumber,
+		/// Number of claims
+		pub claims: u32,
+	}
+
+	/// A reward entry along with its vesting status, as returned by the runtime API
+	#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	pub struct RewardEntryStatus<RelayChainAccountId, Balance, VestingBlockNumber> {
+		pub total_reward: Balance,
+		pub claimed_reward: Balance,
+		pub contributed_relay_addresses: Vec<RelayChainAccountId>,
+		/// Vesting block height at which the entry starts vesting
+		pub vesting_start: VestingBlockNumber,
+		/// Vesting block height at which the entry is fully vested
+		pub vesting_end: VestingBlockNumber,
+		/// Reward vested at the current vesting block, including the initial payment
+		pub vested_reward: Balance,
+		/// Reward vested but not claimed yet. It can only be claimed once associated and
+		/// initialized
+		pub claimable_reward: Balance,
+	}
+
+	pub type RewardEntryStatusOf<T> = RewardEntryStatus<
+		<T as Config>::RelayChainAccountId,
+		BalanceOf<T>,
+		<T as Config>::VestingBlockNumber,
+	>;
+
+	/// Whether a relay account contributed, and if so whether it is associated already
+	#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	pub enum RelayAccountStatus<Balance> {
+		/// The relay account has no reward
+		Unknown,
+		/// The relay account has a reward waiting to be associated
+		Unassociated(Balance),
+		/// The relay account is associated with a native account. Data is its reward, if known
+		Associated(Option<Balance>),
+	}
+
+	/// A round to initialize contributors after the initialization was completed
+	#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct SupplementalRoundInfo<T: Config> {
+		/// Funds of the pot reserved for the rewards of the round
+		pub funds: BalanceOf<T>,
+		/// Rewards initialized in the round so far
+		pub rewards: BalanceOf<T>,
+		/// Contributors initialized in the round so far
+		pub contributors: u32,
+		/// Vesting window of the entries of the round. The global one is used if not provided
+		pub vesting_window: Option<(T::VestingBlockNumber, T::VestingBlockNumber)>,
+	}
+
+	/// A piecewise reward curve with diminishing marginal rates, to disincentivize whales
+	#[derive(
+		Clone,
+		Encode,
+		Decode,
+		DecodeWithMemTracking,
+		RuntimeDebug,
+		PartialEq,
+		Eq,
+		scale_info::TypeInfo,
+	)]
+	pub struct RewardCurveInfo<Balance> {
+		/// `(threshold, rate)` sorted by threshold, with diminishing rates. The part of a reward
+		/// between a threshold and the next one is paid at the rate of the threshold. The part
+		/// below the first threshold is paid in full
+		pub tiers: Vec<(Balance, Perbill)>,
+		/// Maximum reward of a single relay account, applied after the tiers
+		pub cap: Option<Balance>,
+	}
+
+	impl<Balance: PartialOrd> RewardCurveInfo<Balance> {
+		/// Whether thresholds increase and rates diminish from one tier to the next
+		pub fn is_valid(&self) -> bool {
+			self.tiers.len() as u32 <= MAX_REWARD_CURVE_TIERS
+				&& self
+					.tiers
+					.windows(2)
+					.all(|tiers| tiers[0].0 < tiers[1].0 && tiers[0].1 >= tiers[1].1)
+		}
+	}
+
+	/// Whether funds were moved into or out of the pot
+	#[derive(Clone, Copy, Encode, Decode, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo)]
+	pub enum FundingKind {
+		Deposit,
+		Withdrawal,
+	}
+
+	/// A movement of funds into or out of the pot, to reconcile the pot balance with the rewards
+	#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct FundingRecord<T: Config> {
+		pub block: BlockNumberFor<T>,
+		pub account: T::AccountId,
+		pub amount: BalanceOf<T>,
+		pub kind: FundingKind,
 	}
 
 	// This hook is in charge of initializing the vesting height at the first block of the parachain
@@ -170,7 +534,11 @@ pub mod pallet {
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_finalize(n: BlockNumberFor<T>) {
 			// In the first block of the parachain we need to introduce the vesting block related info
-			if n == 1u32.into() {
+			// An explicitly set vesting start (eg in the genesis config) is never overwritten
+			if T::InitVestingAtFirstBlock::get()
+				&& n == 1u32.into()
+				&& !<InitVestingBlock<T>>::exists()
+			{
 				<InitVestingBlock<T>>::put(T::VestingBlockProvider::current_block_number());
 			}
 		}
@@ -183,8 +551,21 @@ pub mod pallet {
 		/// The caller needs to provide the unassociated relay account and a proof to succeed
 		/// with the association
 		/// The proof is nothing but a signature over the reward_address using the relay keys
+		///
+		/// The call is fee-free if it would succeed and make a non-zero initial payment, so that
+		/// an empty native account can be associated
 		#[pallet::call_index(0)]
-		#[pallet::weight(T::WeightInfo::associate_native_identity())]
+		#[pallet::weight(T::WeightInfo::associate_native_identity()
+			.saturating_add(T::OnRewardEvent::weight().saturating_mul(2)))]
+		#[pallet::feeless_if(|
+			origin: &OriginFor<T>,
+			reward_account: &T::AccountId,
+			relay_account: &T::RelayChainAccountId,
+			proof: &MultiSignature,
+		| -> bool {
+			T::RewardAddressChangeOrigin::try_origin(origin.clone()).is_ok()
+				&& Pallet::<T>::is_feeless_association(reward_account, relay_account, proof)
+		})]
 		pub fn associate_native_identity(
 			origin: OriginFor<T>,
 			reward_account: T::AccountId,
@@ -219,37 +600,40 @@ pub mod pallet {
 				Error::<T>::AlreadyAssociated
 			);
 
-			// b"<Bytes>" "SignatureNetworkIdentifier" + "new_account" + b"</Bytes>"
-			let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
-			payload.append(&mut T::SignatureNetworkIdentifier::get().to_vec());
-			payload.append(&mut reward_account.encode());
-			payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
-
 			// Check the signature
 			Self::verify_signatures(
 				vec![(relay_account.clone(), proof)],
 				reward_info.clone(),
-				payload,
+				Self::associate_native_identity_payload(&reward_account),
 			)?;
 
-			// Make the first payment
-			let first_payment = T::InitializationPayment::get() * reward_info.total_reward;
-
-			T::RewardCurrency::transfer(
-				&PALLET_ID.into_account_truncating(),
-				&reward_account,
-				first_payment,
-				AllowDeath,
-			)?;
+			// Record the association block as the vesting start, unless the entry comes with its
+			// own vesting window. It is only honored if PerEntryVesting is set
+			if reward_info.vesting_end.is_none() {
+				reward_info.vesting_start = Some(T::VestingBlockProvider::current_block_number());
+			}
 
-			Self::deposit_event(Event::InitialPaymentMade(
-				reward_account.clone(),
-				first_payment,
-			));
+			// Make the first payment
+			let first_payment =
+				Self::make_initial_payment(&reward_account, reward_info.total_reward)?;
 
 			reward_info.claimed_reward = first_payment;
+			PaidRewardAmount::<T>::mutate(|paid| *paid = paid.saturating_add(first_payment));
+			Statistics::<T>::mutate(|stats| {
+				stats.total_claimed = stats.total_claimed.saturating_add(first_payment);
+				stats.unassociated_rewards = stats
+					.unassociated_rewards
+					.saturating_sub(reward_info.total_reward);
+				stats.associated_rewards = stats
+					.associated_rewards
+					.saturating_add(reward_info.total_reward);
+				stats.associated_relay_accounts = stats
+					.associated_relay_accounts
+					.saturating_add(reward_info.contributed_relay_addresses.len() as u32);
+			});
 
 			// Insert on payable
+			Self::join_redistribution(&mut reward_info);
 			AccountsPayable::<T>::insert(&reward_account, &reward_info);
 
 			// Remove from unassociated
@@ -258,6 +642,12 @@ pub mod pallet {
 			// Insert in mapping
 			ClaimedRelayChainIds::<T>::insert(&relay_account, ());
 
+			T::OnRewardEvent::on_identity_associated(
+				&relay_account,
+				&reward_account,
+				reward_info.total_reward,
+			);
+
 			// Emit Event
 			Self::deposit_event(Event::NativeIdentityAssociated(
 				relay_account,
@@ -275,7 +665,8 @@ pub mod pallet {
 
 		/// Origin must be RewardAddressChangeOrigin
 		#[pallet::call_index(1)]
-		#[pallet::weight(T::WeightInfo::change_association_with_relay_keys(proofs.len() as u32))]
+		#[pallet::weight(T::WeightInfo::change_association_with_relay_keys(proofs.len() as u32)
+			.saturating_add(T::OnRewardEvent::weight()))]
 		pub fn change_association_with_relay_keys(
 			origin: OriginFor<T>,
 			reward_account: T::AccountId,
@@ -308,9 +699,13 @@ pub mod pallet {
 
 			// Remove fromon payable
 			AccountsPayable::<T>::remove(&previous_account);
+			MergeApprovals::<T>::remove(&previous_account);
 
 			// Insert on payable
 			AccountsPayable::<T>::insert(&reward_account, &reward_info);
+			Self::move_claim_record(&previous_account, &reward_account);
+
+			T::OnRewardEvent::on_reward_address_updated(&previous_account, &reward_account);
 
 			// Emit Event
 			Self::deposit_event(Event::RewardAddressUpdated(
@@ -322,66 +717,27 @@ pub mod pallet {
 		}
 
 		/// Collect whatever portion of your reward are currently vested.
+		///
+		/// The call is fee-free if there is a non-zero vested payout, unless the caller already
+		/// claimed within the last FeelessClaimInterval blocks
 		#[pallet::call_index(2)]
-		#[pallet::weight(T::WeightInfo::claim())]
+		#[pallet::weight(T::WeightInfo::claim().saturating_add(T::OnRewardEvent::weight()))]
+		#[pallet::feeless_if(|origin: &OriginFor<T>| -> bool {
+			ensure_signed(origin.clone()).map_or(false, |who| Pallet::<T>::is_feeless_claim(&who))
+		})]
 		pub fn claim(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let payee = ensure_signed(origin)?;
-			let initialized = <Initialized<T>>::get();
-			ensure!(initialized, Error::<T>::RewardVecNotFullyInitializedYet);
-			// Calculate the veted amount on demand.
-			let mut info =
-				AccountsPayable::<T>::get(&payee).ok_or(Error::<T>::NoAssociatedClaim)?;
-			ensure!(
-				info.claimed_reward < info.total_reward,
-				Error::<T>::RewardsAlreadyClaimed
-			);
-
-			// Get the current block used for vesting purposes
-			let now = T::VestingBlockProvider::current_block_number();
-
-			// Substract the first payment from the vested amount
-			let first_paid = T::InitializationPayment::get() * info.total_reward;
-
-			// To calculate how much could the user have claimed already
-			let payable_period = now.saturating_sub(<InitVestingBlock<T>>::get());
-
-			// How much should the contributor have already claimed by this block?
-			// By multiplying first we allow the conversion to integer done with the biggest number
-			let period = EndVestingBlock::<T>::get() - InitVestingBlock::<T>::get();
-			let should_have_claimed = if period == 0u32.into() {
-				// Pallet is configured with a zero vesting period.
-				info.total_reward - first_paid
-			} else {
-				(info.total_reward - first_paid).saturating_mul(payable_period.into())
-					/ period.into()
-			};
-
-			// If the period is bigger than whats missing to pay, then return whats missing to pay
-			let payable_amount = if should_have_claimed >= (info.total_reward - first_paid) {
-				info.total_reward.saturating_sub(info.claimed_reward)
-			} else {
-				should_have_claimed + first_paid - info.claimed_reward
-			};
-
-			info.claimed_reward = info.claimed_reward.saturating_add(payable_amount);
-			AccountsPayable::<T>::insert(&payee, &info);
-
-			// This pallet controls an amount of funds and transfers them to each of the contributors
-			//TODO: contributors should have the balance locked for tranfers but not for democracy
-			T::RewardCurrency::transfer(
-				&PALLET_ID.into_account_truncating(),
-				&payee,
-				payable_amount,
-				AllowDeath,
-			)?;
-			// Emit event
-			Self::deposit_event(Event::RewardsPaid(payee, payable_amount));
+			if let Some(payable_amount) = Self::do_claim(&payee, &payee, None)? {
+				// Emit event
+				Self::deposit_event(Event::RewardsPaid(payee, payable_amount));
+			}
 			Ok(Default::default())
 		}
 
 		/// Update reward address, proving that the caller owns the current native key
 		#[pallet::call_index(3)]
-		#[pallet::weight(T::WeightInfo::update_reward_address())]
+		#[pallet::weight(T::WeightInfo::update_reward_address()
+			.saturating_add(T::OnRewardEvent::weight()))]
 		pub fn update_reward_address(
 			origin: OriginFor<T>,
 			new_reward_account: T::AccountId,
@@ -399,9 +755,13 @@ pub mod pallet {
 
 			// Remove previous rewarded account
 			AccountsPayable::<T>::remove(&signer);
+			MergeApprovals::<T>::remove(&signer);
 
 			// Update new rewarded acount
 			AccountsPayable::<T>::insert(&new_reward_account, &info);
+			Self::move_claim_record(&signer, &new_reward_account);
+
+			T::OnRewardEvent::on_reward_address_updated(&signer, &new_reward_account);
 
 			// Emit event
 			Self::deposit_event(Event::RewardAddressUpdated(signer, new_reward_account));
@@ -413,65 +773,343 @@ pub mod pallet {
 		///  -The reward contribution money matches the crowdloan pot
 		///  -The end vesting block is higher than the init vesting block
 		///  -The initialization has not complete yet
+		/// If 'init_vesting_block' is provided, it replaces the current vesting start
 		#[pallet::call_index(4)]
 		#[pallet::weight(T::WeightInfo::complete_initialization())]
 		pub fn complete_initialization(
 			origin: OriginFor<T>,
 			lease_ending_block: T::VestingBlockNumber,
+			init_vesting_block: Option<T::VestingBlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
 
-			let initialized = <Initialized<T>>::get();
+			Self::do_complete_initialization(lease_ending_block, init_vesting_block)?;
 
-			// This ensures there was no prior initialization
+			Ok(Default::default())
+		}
+
+		/// Initialize the reward distribution storage. Only errors affecting the whole batch
+		/// shortcut, entries that cannot be initialized are skipped and reported in the events
+
+		/// This does not enforce any checks other than making sure we dont go over funds
+		/// complete_initialization should perform any additional
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::initialize_reward_vec(rewards.len() as u32)
+			.saturating_add(T::OnRewardEvent::weight().saturating_mul(rewards.len() as u64)))]
+		pub fn initialize_reward_vec(
+			origin: OriginFor<T>,
+			rewards: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let entries = rewards
+				.into_iter()
+				.map(|(relay_account, native_account, reward)| {
+					(
+						InitializationEntry {
+							relay_account,
+							native_account,
+							reward,
+							early_bird_bonus: Perbill::zero(),
+							referrer: None,
+						},
+						None,
+					)
+				})
+				.collect();
+			Self::do_initialize_reward_vec(entries)?;
+
+			Ok(Default::default())
+		}
+
+		/// Split a reward entry aggregating several relay accounts into separate entries
+		///
+		/// Every relay account in 'splits' is moved out of the entry of 'account' into a new
+		/// entry for the given native account. Each new entry carries the reward of its relay
+		/// accounts and the proportional part of the already claimed reward.
+		/// At least one relay account needs to remain in the original entry.
+		/// Relay accounts of entries that already aggregated several relay accounts before the
+		/// v1 migration have no known reward, so they cannot be split out.
+		///
+		/// The call can be made by 'account' itself. Otherwise the origin must be
+		/// RewardAddressChangeOrigin and the number of valid relay proofs needs to be bigger
+		/// than 'RewardAddressRelayVoteThreshold'
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::split_reward_entry(
+			proofs.len() as u32,
+			splits.len() as u32
+		))]
+		pub fn split_reward_entry(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			splits: Vec<(T::RelayChainAccountId, T::AccountId)>,
+			proofs: Vec<(T::RelayChainAccountId, MultiSignature)>,
+		) -> DispatchResultWithPostInfo {
+			let mut reward_info =
+				AccountsPayable::<T>::get(&account).ok_or(Error::<T>::NoAssociatedClaim)?;
+			// There is nothing left to split, and the relay rewards of entries closed with
+			// exit_early do not add up to their total anymore
 			ensure!(
-				initialized == false,
-				Error::<T>::RewardVecAlreadyInitialized
+				!Self::is_fully_claimed(&reward_info),
+				Error::<T>::RewardsAlreadyClaimed
 			);
 
-			// This ensures the end vesting block (when all funds are fully vested)
-			// is bigger than the init vesting block
+			// The owner of the entry does not need to present any proof
+			let is_owner = ensure_signed(origin.clone()).map_or(false, |signer| signer == account);
+			if !is_owner {
+				T::RewardAddressChangeOrigin::ensure_origin(origin)?;
+				let payload = Self::split_reward_entry_payload(&account, &splits);
+				Self::verify_signatures(proofs, reward_info.clone(), payload)?;
+			}
+
+			// The redistributed rewards owed so far stay with the original entry
+			Self::settle_redistribution(&mut reward_info);
+			let shares_before = Self::redistribution_shares(&reward_info);
+
+			let original_total = reward_info.total_reward;
+			let original_claimed = reward_info.claimed_reward;
+			let original_bonus = reward_info.bonus.clone();
+			let was_fully_claimed = Self::is_fully_claimed(&reward_info);
+
+			// I use a map here to group the relay accounts going to the same native account
+			let mut new_entries: BTreeMap<T::AccountId, RewardInfo<T>> = BTreeMap::new();
+			for (relay_account, new_account) in splits {
+				// For now I prefer that we dont support providing an existing account here
+				ensure!(
+					new_account != account && AccountsPayable::<T>::get(&new_account).is_none(),
+					Error::<T>::AlreadyAssociated
+				);
+
+				let position = reward_info
+					.contributed_relay_addresses
+					.iter()
+					.position(|address| address == &relay_account)
+					.ok_or(Error::<T>::NonContributedAddressProvided)?;
+				let relay_reward =
+					RelayRewards::<T>::get(&relay_account).ok_or(Error::<T>::UnknownRelayReward)?;
+
+				// The new entry carries the proportional part of what was already claimed, and of
+				// the early-bird bonus
+				let relay_share = Perquintill::from_rational(relay_reward, original_total);
+				let relay_claimed = relay_share * original_claimed;
+				let relay_early_bird = relay_share * original_bonus.early_bird;
+				let relay_early_bird_paid = relay_share * original_bonus.early_bird_paid;
+
+				reward_info.contributed_relay_addresses.remove(position);
+				reward_info.total_reward = reward_info.total_reward.saturating_sub(relay_reward);
+				reward_info.claimed_reward =
+					reward_info.claimed_reward.saturating_sub(relay_claimed);
+				reward_info.bonus.early_bird = reward_info
+					.bonus
+					.early_bird
+					.saturating_sub(relay_early_bird);
+				reward_info.bonus.early_bird_paid = reward_info
+					.bonus
+					.early_bird_paid
+					.saturating_sub(relay_early_bird_paid);
+
+				let new_entry = new_entries
+					.entry(new_account)
+					.or_insert_with(|| RewardInfo {
+						total_reward: 0u32.into(),
+						claimed_reward: 0u32.into(),
+						contributed_relay_addresses: vec![],
+						vesting_start: reward_info.vesting_start.clone(),
+						vesting_end: reward_info.vesting_end.clone(),
+						contribution: None,
+						bonus: BonusInfo {
+							loyalty_settled: reward_info.bonus.loyalty_settled,
+							..Default::default()
+						},
+						redistribution: RedistributionInfo {
+							index: RedistributionIndex::<T>::get(),
+							pending: Zero::zero(),
+						},
+					});
+				new_entry.total_reward = new_entry.total_reward.saturating_add(relay_reward);
+				new_entry.claimed_reward = new_entry.claimed_reward.saturating_add(relay_claimed);
+				new_entry.bonus.early_bird =
+					new_entry.bonus.early_bird.saturating_add(relay_early_bird);
+				new_entry.bonus.early_bird_paid = new_entry
+					.bonus
+					.early_bird_paid
+					.saturating_add(relay_early_bird_paid);
+				new_entry.contributed_relay_addresses.push(relay_account);
+			}
+
+			// Moving every relay account is just a reward address change
 			ensure!(
-				lease_ending_block > InitVestingBlock::<T>::get(),
-				Error::<T>::VestingPeriodNonValid
+				!reward_info.contributed_relay_addresses.is_empty(),
+				Error::<T>::CannotSplitEntireEntry
 			);
 
-			let current_initialized_rewards = InitializedRewardAmount::<T>::get();
+			// Each entry keeps the contributions of its own relay accounts
+			reward_info.contribution =
+				Self::relay_accounts_contribution(&reward_info.contributed_relay_addresses);
+			for new_entry in new_entries.values_mut() {
+				new_entry.contribution =
+					Self::relay_accounts_contribution(&new_entry.contributed_relay_addresses);
+			}
 
-			let reward_difference = Self::pot().saturating_sub(current_initialized_rewards);
+			AccountsPayable::<T>::insert(&account, &reward_info);
 
-			// Ensure the difference is not bigger than the total number of contributors
-			ensure!(
-				reward_difference < TotalContributors::<T>::get().into(),
-				Error::<T>::RewardsDoNotMatchFund
+			let fully_claimed = sp_std::iter::once(&reward_info)
+				.chain(new_entries.values())
+				.filter(|info| Self::is_fully_claimed(info))
+				.count();
+			Self::update_fully_claimed_accounts(was_fully_claimed as usize, fully_claimed);
+			let shares_after = new_entries.values().fold(
+				Self::redistribution_shares(&reward_info),
+				|shares, new_entry| shares.saturating_add(Self::redistribution_shares(new_entry)),
 			);
+			Self::update_redistribution_shares(shares_before, shares_after);
+
+			for (new_account, new_entry) in new_entries {
+				AccountsPayable::<T>::insert(&new_account, &new_entry);
+				Self::deposit_event(Event::RewardEntrySplit(
+					account.clone(),
+					new_account,
+					new_entry.total_reward,
+				));
+			}
 
-			// Burn the difference
-			let imbalance = T::RewardCurrency::withdraw(
-				&PALLET_ID.into_account_truncating(),
-				reward_difference,
-				WithdrawReasons::TRANSFER,
-				AllowDeath,
-			)
-			.expect("Shouldnt fail, as the fund should be enough to burn and nothing is locked");
-			drop(imbalance);
+			Ok(Default::default())
+		}
 
-			EndVestingBlock::<T>::put(lease_ending_block);
+		/// Approve merging the reward entry of the caller with the entry of 'other_account'
+		///
+		/// A merge is authorized for an entry if its owner approved it, or if enough relay
+		/// proofs are presented in merge_reward_entries. Passing None revokes the approval
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::approve_merge())]
+		pub fn approve_merge(
+			origin: OriginFor<T>,
+			other_account: Option<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			let signer = ensure_signed(origin)?;
 
-			<Initialized<T>>::put(true);
+			ensure!(
+				AccountsPayable::<T>::get(&signer).is_some(),
+				Error::<T>::NoAssociatedClaim
+			);
+
+			if let Some(other_account) = other_account {
+				ensure!(other_account != signer, Error::<T>::CannotMergeWithItself);
+				MergeApprovals::<T>::insert(&signer, &other_account);
+				Self::deposit_event(Event::MergeApproved(signer, other_account));
+			} else {
+				MergeApprovals::<T>::remove(&signer);
+			}
 
 			Ok(Default::default())
 		}
 
-		/// Initialize the reward distribution storage. It shortcuts whenever an error is found
+		/// Merge the reward entry of 'from' into the entry of 'into'
+		///
+		/// Totals, claimed amounts and contributed relay addresses are combined in 'into'.
+		/// Each of the two entries needs to be authorized, either by its owner (signing this
+		/// call or having approved the merge with approve_merge) or by relay proofs whose number
+		/// needs to be bigger than 'RewardAddressRelayVoteThreshold'
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::merge_reward_entries(
+			from_proofs.len() as u32,
+			into_proofs.len() as u32
+		))]
+		pub fn merge_reward_entries(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			into: T::AccountId,
+			from_proofs: Vec<(T::RelayChainAccountId, MultiSignature)>,
+			into_proofs: Vec<(T::RelayChainAccountId, MultiSignature)>,
+		) -> DispatchResultWithPostInfo {
+			ensure!(from != into, Error::<T>::CannotMergeWithItself);
+
+			let mut from_info =
+				AccountsPayable::<T>::get(&from).ok_or(Error::<T>::NoAssociatedClaim)?;
+			let mut into_info =
+				AccountsPayable::<T>::get(&into).ok_or(Error::<T>::NoAssociatedClaim)?;
+
+			let signer = ensure_signed(origin.clone()).ok();
+			let from_approved = signer.as_ref() == Some(&from)
+				|| MergeApprovals::<T>::get(&from).as_ref() == Some(&into);
+			let into_approved = signer.as_ref() == Some(&into)
+				|| MergeApprovals::<T>::get(&into).as_ref() == Some(&from);
+
+			// Whatever the owners did not approve needs to be proven with relay keys
+			if !from_approved || !into_approved {
+				T::RewardAddressChangeOrigin::ensure_origin(origin)?;
+				let payload = Self::merge_reward_entries_payload(&from, &into);
+				if !from_approved {
+					Self::verify_signatures(from_proofs, from_info.clone(), payload.clone())?;
+				}
+				if !into_approved {
+					Self::verify_signatures(into_proofs, into_info.clone(), payload)?;
+				}
+			}
 
-		/// This does not enforce any checks other than making sure we dont go over funds
-		/// complete_initialization should perform any additional
-		#[pallet::call_index(5)]
-		#[pallet::weight(T::WeightInfo::initialize_reward_vec(rewards.len() as u32))]
-		pub fn initialize_reward_vec(
+			let fully_claimed_before = Self::is_fully_claimed(&from_info) as usize
+				+ Self::is_fully_claimed(&into_info) as usize;
+			Self::settle_redistribution(&mut from_info);
+			Self::settle_redistribution(&mut into_info);
+			let shares_before = Self::redistribution_shares(&from_info)
+				.saturating_add(Self::redistribution_shares(&into_info));
+
+			// Merging can never accelerate vesting
+			(into_info.vesting_start, into_info.vesting_end) =
+				Self::combined_vesting_window(&into_info, &from_info);
+
+			let RewardInfo {
+				total_reward,
+				claimed_reward,
+				mut contributed_relay_addresses,
+				contribution,
+				bonus,
+				redistribution,
+				..
+			} = from_info;
+			into_info.total_reward = into_info.total_reward.saturating_add(total_reward);
+			into_info.contribution =
+				Self::combined_contribution(into_info.contribution, contribution);
+			into_info.bonus = Self::combined_bonus(into_info.bonus.clone(), bonus);
+			into_info.claimed_reward = into_info.claimed_reward.saturating_add(claimed_reward);
+			into_info.redistribution.pending = into_info
+				.redistribution
+				.pending
+				.saturating_add(redistribution.pending);
+			into_info
+				.contributed_relay_addresses
+				.append(&mut contributed_relay_addresses);
+
+			AccountsPayable::<T>::remove(&from);
+			AccountsPayable::<T>::insert(&into, &into_info);
+			MergeApprovals::<T>::remove(&from);
+			MergeApprovals::<T>::remove(&into);
+			ClaimRecords::<T>::remove(&from);
+			Self::update_fully_claimed_accounts(
+				fully_claimed_before,
+				Self::is_fully_claimed(&into_info) as usize,
+			);
+			Self::update_redistribution_shares(
+				shares_before,
+				Self::redistribution_shares(&into_info),
+			);
+
+			Self::deposit_event(Event::RewardEntriesMerged(
+				from,
+				into,
+				into_info.total_reward,
+			));
+
+			Ok(Default::default())
+		}
+
+		/// Set the vesting block height at which vesting starts
+		///
+		/// This can only be done before the initialization is completed
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::set_init_vesting_block())]
+		pub fn set_init_vesting_block(
 			origin: OriginFor<T>,
-			rewards: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)>,
+			init_vesting_block: T::VestingBlockNumber,
 		) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
 			let initialized = <Initialized<T>>::get();
@@ -480,44 +1118,1354 @@ pub mod pallet {
 				Error::<T>::RewardVecAlreadyInitialized
 			);
 
-			// Ensure we are below the max number of contributors
-			ensure!(
-				rewards.len() as u32 <= T::MaxInitContributors::get(),
-				Error::<T>::TooManyContributors
-			);
+			InitVestingBlock::<T>::put(init_vesting_block.clone());
 
-			// What is the amount initialized so far?
-			let mut current_initialized_rewards = InitializedRewardAmount::<T>::get();
+			Self::deposit_event(Event::InitVestingBlockSet(init_vesting_block));
 
-			// Total number of contributors
-			let mut total_contributors = TotalContributors::<T>::get();
+			Ok(Default::default())
+		}
 
-			let incoming_rewards: BalanceOf<T> = rewards
-				.iter()
-				.fold(0u32.into(), |acc: BalanceOf<T>, (_, _, reward)| {
-					acc + *reward
-				});
+		/// Fund the pot with funds from the source account
+		///
+		/// The deposit is kept in the funding history
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::fund_pot())]
+		pub fn fund_pot(
+			origin: OriginFor<T>,
+			source: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			T::RewardCurrency::transfer(&source, &Self::account_id(), amount, AllowDeath)?;
+
+			Self::record_funding(source.clone(), amount, FundingKind::Deposit);
+			Self::deposit_event(Event::PotFunded(source, amount));
 
-			// Ensure we dont go over funds
+			Ok(Default::default())
+		}
+
+		/// Withdraw funds from the pot that are not needed to pay the outstanding rewards
+		///
+		/// The withdrawal is kept in the funding history
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::withdraw_excess())]
+		pub fn withdraw_excess(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			// The pot needs to keep enough funds to pay every contributor
 			ensure!(
-				current_initialized_rewards + incoming_rewards <= Self::pot(),
-				Error::<T>::BatchBeyondFundPot
+				amount <= Self::excess_funds(),
+				Error::<T>::WithdrawalBeyondExcessFunds
 			);
 
-			for (relay_account, native_account, reward) in &rewards {
-				if ClaimedRelayChainIds::<T>::get(&relay_account).is_some()
-					|| UnassociatedContributions::<T>::get(&relay_account).is_some()
-				{
-					// Dont fail as this is supposed to be called with batch calls and we
-					// dont want to stall the rest of the contributions
-					Self::deposit_event(Event::InitializedAlreadyInitializedAccount(
-						relay_account.clone(),
-						native_account.clone(),
-						*reward,
-					));
+			T::RewardCurrency::transfer(&Self::account_id(), &dest, amount, AllowDeath)?;
+
+			Self::record_funding(dest.clone(), amount, FundingKind::Withdrawal);
+			Self::deposit_event(Event::ExcessWithdrawn(dest, amount));
+
+			Ok(Default::default())
+		}
+
+		/// Unwind the campaign, eg if the auction was lost, removing up to `limit` reward entries
+		///
+		/// Initial payments are returned to the pot where possible, ie as far as the contributor
+		/// still holds them above the existential deposit. Once every entry is removed
+		/// the counters are reset and the remaining pot, along with the bonus pot, is sent to
+		/// ResetPotDestination. Needs to be called until CampaignReset is emitted, and only before
+		/// complete_initialization
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::reset_campaign(*limit))]
+		pub fn reset_campaign(origin: OriginFor<T>, limit: u32) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let initialized = <Initialized<T>>::get();
+			ensure!(
+				initialized == false,
+				Error::<T>::RewardVecAlreadyInitialized
+			);
+
+			let mut removed = 0u32;
+
+			let payable: Vec<_> = AccountsPayable::<T>::drain().take(limit as usize).collect();
+			for (account, reward_info) in payable {
+				removed += 1;
+				// Return the payments made so far, as far as the account still holds them above the
+				// existential deposit. The account is never reaped
+				let refundable = reward_info.claimed_reward.min(
+					T::RewardCurrency::free_balance(&account)
+						.saturating_sub(T::RewardCurrency::minimum_balance()),
+				);
+				if !refundable.is_zero()
+					&& T::RewardCurrency::transfer(
+						&account,
+						&Self::account_id(),
+						refundable,
+						KeepAlive,
+					)
+					.is_ok()
+				{
+					Self::deposit_event(Event::InitialPaymentRefunded(account.clone(), refundable));
+				}
+				for relay_account in reward_info.contributed_relay_addresses {
+					ClaimedRelayChainIds::<T>::remove(&relay_account);
+					RelayRewards::<T>::remove(&relay_account);
+					RelayContributions::<T>::remove(&relay_account);
+				}
+				MergeApprovals::<T>::remove(&account);
+				ClaimRecords::<T>::remove(&account);
+			}
+
+			let unassociated: Vec<_> = UnassociatedContributions::<T>::drain()
+				.take(limit.saturating_sub(removed) as usize)
+				.collect();
+			for (relay_account, _) in unassociated {
+				removed += 1;
+				RelayRewards::<T>::remove(&relay_account);
+				RelayContributions::<T>::remove(&relay_account);
+			}
+
+			if AccountsPayable::<T>::iter_keys().next().is_some()
+				|| UnassociatedContributions::<T>::iter_keys().next().is_some()
+			{
+				Self::deposit_event(Event::CampaignResetProgressed(removed));
+				return Ok(Default::default());
+			}
+
+			InitializedRewardAmount::<T>::kill();
+			TotalContributors::<T>::kill();
+			PaidRewardAmount::<T>::kill();
+			Statistics::<T>::kill();
+			InitializedBonusAmount::<T>::kill();
+			PaidBonusAmount::<T>::kill();
+			RedistributionIndex::<T>::kill();
+			TotalRedistributionShares::<T>::kill();
+			ForfeitedRewardAmount::<T>::kill();
+			SupplementalRound::<T>::kill();
+
+			// Send what is left to the configured destination
+			let destination = T::ResetPotDestination::get();
+			let remaining = Self::pot();
+			T::RewardCurrency::transfer(&Self::account_id(), &destination, remaining, AllowDeath)?;
+			Self::record_funding(destination.clone(), remaining, FundingKind::Withdrawal);
+			T::RewardCurrency::transfer(
+				&Self::bonus_account_id(),
+				&destination,
+				Self::bonus_pot(),
+				AllowDeath,
+			)?;
+
+			Self::deposit_event(Event::CampaignReset(destination, remaining));
+
+			Ok(Default::default())
+		}
+
+		/// Open a supplemental round to initialize late contributors after the initialization
+		///
+		/// `funds` need to be available in the pot beyond the outstanding rewards, eg through
+		/// fund_pot. The entries of the round vest in `vesting_window` if provided, or in the
+		/// global vesting window otherwise
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::open_supplemental_round())]
+		pub fn open_supplemental_round(
+			origin: OriginFor<T>,
+			funds: BalanceOf<T>,
+			vesting_window: Option<(T::VestingBlockNumber, T::VestingBlockNumber)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let initialized = <Initialized<T>>::get();
+			ensure!(initialized, Error::<T>::RewardVecNotFullyInitializedYet);
+			ensure!(
+				!SupplementalRound::<T>::exists(),
+				Error::<T>::SupplementalRoundAlreadyOpen
+			);
+			if let Some((vesting_start, vesting_end)) = &vesting_window {
+				ensure!(
+					vesting_end > vesting_start,
+					Error::<T>::VestingPeriodNonValid
+				);
+			}
+			ensure!(
+				funds <= Self::excess_funds(),
+				Error::<T>::InsufficientFundsForSupplementalRound
+			);
+
+			SupplementalRound::<T>::put(SupplementalRoundInfo {
+				funds,
+				rewards: 0u32.into(),
+				contributors: 0,
+				vesting_window,
+			});
+
+			Self::deposit_event(Event::SupplementalRoundOpened(funds));
+
+			Ok(Default::default())
+		}
+
+		/// Complete the supplemental round
+		///
+		/// As in complete_initialization, the round funds need to match the rewards initialized
+		/// in the round, and the difference is handed over to the InitializationDust handler
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::complete_supplemental_round())]
+		pub fn complete_supplemental_round(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let round = SupplementalRound::<T>::get().ok_or(Error::<T>::NoSupplementalRound)?;
+
+			let reward_difference = round.funds.saturating_sub(round.rewards);
+
+			// Ensure the difference is not bigger than the number of contributors of the round
+			ensure!(
+				reward_difference < round.contributors.into(),
+				Error::<T>::RewardsDoNotMatchFund
+			);
+
+			// Hand the difference over to the dust handler
+			let imbalance = T::RewardCurrency::withdraw(
+				&PALLET_ID.into_account_truncating(),
+				reward_difference,
+				WithdrawReasons::TRANSFER,
+				AllowDeath,
+			)?;
+			T::InitializationDust::on_unbalanced(imbalance);
+			if !reward_difference.is_zero() {
+				Self::deposit_event(Event::InitializationDustHandled(reward_difference));
+			}
+
+			SupplementalRound::<T>::kill();
+
+			Self::deposit_event(Event::SupplementalRoundCompleted(
+				round.rewards,
+				round.contributors,
+			));
+
+			Ok(Default::default())
+		}
+
+		/// Close the supplemental round without handing its unused funds over
+		///
+		/// Meant for rounds that got fewer contributors than planned, whose rewards do not match
+		/// their funds. The funds not used by the round are released back to the excess funds of
+		/// the pot, where they can be recovered with withdraw_excess
+		#[pallet::call_index(24)]
+		#[pallet::weight(T::WeightInfo::close_supplemental_round())]
+		pub fn close_supplemental_round(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let round = SupplementalRound::<T>::take().ok_or(Error::<T>::NoSupplementalRound)?;
+
+			Self::deposit_event(Event::SupplementalRoundClosed(
+				round.rewards,
+				round.contributors,
+				round.funds.saturating_sub(round.rewards),
+			));
+
+			Ok(Default::default())
+		}
+
+		/// Collect whatever portion of your reward are currently vested, and stake it right away
+		/// with the given target through the StakingAdapter
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::claim()
+			.saturating_add(T::OnRewardEvent::weight())
+			.saturating_add(T::StakingAdapter::weight()))]
+		pub fn claim_and_stake(
+			origin: OriginFor<T>,
+			target: StakingTargetOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let payee = ensure_signed(origin)?;
+			// Nothing is staked if the payout was deferred
+			if let Some(payable_amount) = Self::do_claim(&payee, &payee, None)? {
+				Self::deposit_event(Event::RewardsPaid(payee.clone(), payable_amount));
+
+				T::StakingAdapter::stake(&payee, target, payable_amount)?;
+				Self::deposit_event(Event::RewardsStaked(payee, payable_amount));
+			}
+
+			Ok(Default::default())
+		}
+
+		/// Collect `amount` out of your currently vested reward, or all of it if not provided,
+		/// into `dest`. The reward address of the entry is not changed
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::claim().saturating_add(T::OnRewardEvent::weight()))]
+		pub fn claim_to(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			amount: Option<BalanceOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			let payee = ensure_signed(origin)?;
+			if let Some(payable_amount) = Self::do_claim(&payee, &dest, amount)? {
+				Self::deposit_event(Event::RewardsPaidTo(payee, dest, payable_amount));
+			}
+			Ok(Default::default())
+		}
+
+		/// Initialize the rewards of the given relay accounts from their contributions to the
+		/// CrowdloanFundIndex crowdloan, proven by a storage proof of the relay chain state
+		///
+		/// Anyone can call this, since the contributions are verified against the relay state root
+		/// pinned with snapshot_relay_state_root. The proof needs to be built at the relay block of
+		/// that root. The rewards are converted with RewardConversion and initialized like in
+		/// initialize_contributions, without a native account to be associated later.
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::ingest_relay_contributions(
+			relay_accounts.len() as u32,
+			proof.encoded_size() as u32
+		)
+		.saturating_add(T::OnRewardEvent::weight().saturating_mul(relay_accounts.len() as u64)))]
+		pub fn ingest_relay_contributions(
+			origin: OriginFor<T>,
+			relay_accounts: Vec<T::RelayChainAccountId>,
+			proof: Vec<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let relay_state_root =
+				RelayStateRootSnapshot::<T>::get().ok_or(Error::<T>::RelayStateRootUnavailable)?;
+			let accounts: Vec<AccountId32> = relay_accounts
+				.iter()
+				.map(|relay_account| relay_account.clone().into())
+				.collect();
+			let contributions = RelayStateProof::new(relay_state_root, proof)
+				.read_contributions(T::CrowdloanFundIndex::get(), &accounts)
+				.map_err(|_| Error::<T>::InvalidRelayStateProof)?;
+
+			let entries = relay_accounts
+				.into_iter()
+				.zip(contributions)
+				.map(|(relay_account, contribution)| {
+					(
+						InitializationEntry {
+							relay_account,
+							native_account: None,
+							reward: T::RewardConversion::convert(contribution),
+							early_bird_bonus: Perbill::zero(),
+							referrer: None,
+						},
+						Some(contribution),
+					)
+				})
+				.collect();
+			Self::do_initialize_reward_vec(entries)?;
+
+			Ok(Default::default())
+		}
+
+		/// Initialize the reward distribution storage from the relay tokens each account
+		/// contributed, converting them into rewards with RewardConversion
+		///
+		/// Works like initialize_reward_vec otherwise. The contributions are kept in the reward
+		/// entries for auditing
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::initialize_contributions(contributions.len() as u32)
+			.saturating_add(T::OnRewardEvent::weight().saturating_mul(contributions.len() as u64)))]
+		pub fn initialize_contributions(
+			origin: OriginFor<T>,
+			contributions: Vec<(T::RelayChainAccountId, Option<T::AccountId>, u128)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let entries = contributions
+				.into_iter()
+				.map(|(relay_account, native_account, contribution)| {
+					(
+						InitializationEntry {
+							relay_account,
+							native_account,
+							reward: T::RewardConversion::convert(contribution),
+							early_bird_bonus: Perbill::zero(),
+							referrer: None,
+						},
+						Some(contribution),
+					)
+				})
+				.collect();
+			Self::do_initialize_reward_vec(entries)?;
+
+			Ok(Default::default())
+		}
+
+		/// Set the curve applied to the rewards of the contributors initialized from now on,
+		/// whether given directly or converted from their contributions. None removes it
+		///
+		/// The part of each reward above the thresholds of the curve is paid at diminishing rates,
+		/// and is then capped. The reduction is reported with InitializedRewardReduced
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::set_reward_curve(
+			curve.as_ref().map_or(0, |curve| curve.tiers.len() as u32)
+		))]
+		pub fn set_reward_curve(
+			origin: OriginFor<T>,
+			curve: Option<RewardCurveInfo<BalanceOf<T>>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			if let Some(curve) = &curve {
+				ensure!(curve.is_valid(), Error::<T>::InvalidRewardCurve);
+			}
+			RewardCurve::<T>::set(curve.clone());
+
+			Self::deposit_event(Event::RewardCurveSet(curve));
+
+			Ok(Default::default())
+		}
+
+		/// Initialize the reward distribution storage with entries carrying bonus and referral
+		/// metadata
+		///
+		/// Works like initialize_reward_vec otherwise. The early-bird bonus of every entry is
+		/// granted on top of its reward and needs to fit in the bonus pot. The referrer of every
+		/// entry, if any, is credited with ReferralReward out of the pot. Referrers need to be
+		/// initialized before, possibly earlier in the same batch
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::initialize_reward_entries(entries.len() as u32)
+			.saturating_add(T::OnRewardEvent::weight().saturating_mul(entries.len() as u64)))]
+		pub fn initialize_reward_entries(
+			origin: OriginFor<T>,
+			entries: Vec<InitializationEntryOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let entries = entries.into_iter().map(|entry| (entry, None)).collect();
+			Self::do_initialize_reward_vec(entries)?;
+
+			Ok(Default::default())
+		}
+
+		/// Fund the bonus pot, from which early-bird and loyalty bonuses are paid, with funds
+		/// from the source account
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::fund_bonus_pot())]
+		pub fn fund_bonus_pot(
+			origin: OriginFor<T>,
+			source: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			T::RewardCurrency::transfer(&source, &Self::bonus_account_id(), amount, AllowDeath)?;
+
+			Self::deposit_event(Event::BonusPotFunded(source, amount));
+
+			Ok(Default::default())
+		}
+
+		/// Collect your vested reward along with part of the unvested reward right away, and
+		/// close your reward entry
+		///
+		/// The EarlyExitPenalty of the unvested reward, decreasing as the vesting period elapses,
+		/// is forfeited and handed over to PenaltyDestination. So is the early-bird bonus of the
+		/// forfeited reward, which goes back to the bonus pot, and the loyalty bonus. As in claim,
+		/// a payout that would leave you below the existential deposit is deferred
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::exit_early().saturating_add(T::OnRewardEvent::weight()))]
+		pub fn exit_early(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let payee = ensure_signed(origin)?;
+			let initialized = <Initialized<T>>::get();
+			ensure!(initialized, Error::<T>::RewardVecNotFullyInitializedYet);
+			let mut info =
+				AccountsPayable::<T>::get(&payee).ok_or(Error::<T>::NoAssociatedClaim)?;
+			ensure!(
+				info.claimed_reward < info.total_reward,
+				Error::<T>::RewardsAlreadyClaimed
+			);
+			Self::settle_redistribution(&mut info);
+			let shares_before = Self::redistribution_shares(&info);
+
+			let now = T::VestingBlockProvider::current_block_number();
+			let vested = Self::vested_reward(&info, now.clone());
+			let unvested = info.total_reward.saturating_sub(vested);
+
+			let (start, end) = Self::vesting_window(&info);
+			let elapsed = if end > start {
+				let elapsed_blocks: BalanceOf<T> = now.clone().saturating_sub(start.clone()).into();
+				let period: BalanceOf<T> = end.saturating_sub(start).into();
+				Perquintill::from_rational(elapsed_blocks, period)
+			} else {
+				Perquintill::one()
+			};
+			let forfeited = T::EarlyExitPenalty::penalty(elapsed) * unvested;
+			let payable_amount = vested
+				.saturating_sub(info.claimed_reward)
+				.saturating_add(unvested.saturating_sub(forfeited));
+			let redistributed = sp_std::mem::take(&mut info.redistribution.pending);
+
+			// As in claim, a payout that would leave the payee below the existential deposit is
+			// deferred and the entry stays open
+			if !Self::can_receive(&payee, payable_amount.saturating_add(redistributed)) {
+				Self::deposit_event(Event::PayoutDeferred(
+					payee,
+					payable_amount.saturating_add(redistributed),
+				));
+				return Ok(Default::default());
+			}
+
+			// The early-bird bonus of the forfeited reward is released, and so is the loyalty
+			// bonus, as the entry is closed before vesting end
+			let early_bird_kept = Perquintill::from_rational(
+				info.total_reward.saturating_sub(forfeited),
+				info.total_reward,
+			) * info.bonus.early_bird;
+			let released_bonus = info.bonus.early_bird.saturating_sub(early_bird_kept);
+			info.bonus.early_bird = early_bird_kept;
+			info.bonus.loyalty_settled = true;
+			InitializedBonusAmount::<T>::mutate(|granted| {
+				*granted = granted.saturating_sub(released_bonus)
+			});
+
+			// Close the entry: whatever was not forfeited is claimed
+			info.total_reward = info.total_reward.saturating_sub(forfeited);
+			let claimed_before = info.claimed_reward;
+			info.claimed_reward = info.total_reward;
+			let (early_bird, _) = Self::settle_bonuses(&mut info, claimed_before, now);
+			Self::update_redistribution_shares(shares_before, Zero::zero());
+			AccountsPayable::<T>::insert(&payee, &info);
+			PaidRewardAmount::<T>::mutate(|paid| {
+				*paid = paid
+					.saturating_add(payable_amount)
+					.saturating_add(redistributed)
+			});
+			ForfeitedRewardAmount::<T>::mutate(|total| *total = total.saturating_add(forfeited));
+			Statistics::<T>::mutate(|stats| {
+				stats.total_claimed = stats
+					.total_claimed
+					.saturating_add(payable_amount)
+					.saturating_add(redistributed);
+				stats.associated_rewards = stats.associated_rewards.saturating_sub(forfeited);
+				stats.fully_claimed_accounts = stats.fully_claimed_accounts.saturating_add(1);
+			});
+			ClaimRecords::<T>::mutate(&payee, |record| {
+				let record = record.get_or_insert_with(Default::default);
+				record.last_claim_block = frame_system::Pallet::<T>::block_number();
+				record.claims = record.claims.saturating_add(1);
+			});
+
+			Self::pay_from_pot(&payee, payable_amount, redistributed)?;
+			Self::pay_bonuses(&payee, early_bird, Zero::zero())?;
+			T::OnRewardEvent::on_reward_paid(
+				&payee,
+				payable_amount
+					.saturating_add(redistributed)
+					.saturating_add(early_bird),
+			);
+
+			// Hand the forfeited reward over to the penalty handler
+			let imbalance = T::RewardCurrency::withdraw(
+				&PALLET_ID.into_account_truncating(),
+				forfeited,
+				WithdrawReasons::TRANSFER,
+				AllowDeath,
+			)?;
+			T::PenaltyDestination::on_unbalanced(imbalance);
+
+			Self::deposit_event(Event::ExitedEarly(payee, payable_amount, forfeited));
+			Ok(Default::default())
+		}
+
+		/// Share funds in the pot beyond the outstanding rewards among the associated entries not
+		/// fully claimed yet, pro rata of their total reward. Each entry gets its share along with
+		/// its next claim
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::WeightInfo::redistribute_excess())]
+		pub fn redistribute_excess(
+			origin: OriginFor<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			// The pot needs to keep enough funds to pay every contributor
+			ensure!(
+				amount <= Self::excess_funds(),
+				Error::<T>::WithdrawalBeyondExcessFunds
+			);
+			ensure!(
+				!Self::redistribute(amount).is_zero(),
+				Error::<T>::NothingToRedistribute
+			);
+
+			Ok(Default::default())
+		}
+
+		/// Sweep the rewards of unassociated entries that were still not associated at the end of
+		/// their vesting period
+		///
+		/// The swept rewards are shared among the associated entries not fully claimed yet, as in
+		/// redistribute_excess. Without such entries they stay in the pot as excess funds. The
+		/// swept relay accounts are marked as claimed, so they cannot be initialized again
+		#[pallet::call_index(25)]
+		#[pallet::weight(T::WeightInfo::sweep_unassociated(relay_accounts.len() as u32))]
+		pub fn sweep_unassociated(
+			origin: OriginFor<T>,
+			relay_accounts: Vec<T::RelayChainAccountId>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let initialized = <Initialized<T>>::get();
+			ensure!(initialized, Error::<T>::RewardVecNotFullyInitializedYet);
+
+			let now = T::VestingBlockProvider::current_block_number();
+			let mut swept_rewards = BalanceOf::<T>::zero();
+			let mut swept_bonuses = BalanceOf::<T>::zero();
+			for relay_account in &relay_accounts {
+				let reward_info = UnassociatedContributions::<T>::take(relay_account)
+					.ok_or(Error::<T>::NoAssociatedClaim)?;
+				let (_, vesting_end) = Self::vesting_window(&reward_info);
+				ensure!(now >= vesting_end, Error::<T>::SweepBeforeVestingEnd);
+
+				// The relay account can not be initialized again, eg in a supplemental round
+				ClaimedRelayChainIds::<T>::insert(relay_account, ());
+				RelayRewards::<T>::remove(relay_account);
+				RelayContributions::<T>::remove(relay_account);
+				Statistics::<T>::mutate(|stats| {
+					stats.unassociated_rewards = stats
+						.unassociated_rewards
+						.saturating_sub(reward_info.total_reward)
+				});
+				swept_rewards = swept_rewards.saturating_add(
+					reward_info
+						.total_reward
+						.saturating_sub(reward_info.claimed_reward),
+				);
+				swept_bonuses = swept_bonuses.saturating_add(
+					reward_info
+						.bonus
+						.early_bird
+						.saturating_sub(reward_info.bonus.early_bird_paid),
+				);
+			}
+
+			// The swept rewards and bonuses are not owed anymore
+			TotalContributors::<T>::mutate(|total| {
+				*total = total.saturating_sub(relay_accounts.len() as u32)
+			});
+			InitializedRewardAmount::<T>::mutate(|initialized| {
+				*initialized = initialized.saturating_sub(swept_rewards)
+			});
+			InitializedBonusAmount::<T>::mutate(|initialized| {
+				*initialized = initialized.saturating_sub(swept_bonuses)
+			});
+			Self::deposit_event(Event::UnassociatedRewardsSwept(
+				relay_accounts.len() as u32,
+				swept_rewards,
+			));
+			Self::redistribute(swept_rewards);
+
+			Ok(Default::default())
+		}
+
+		/// Pin the current relay state root given by RelayStateRoot, eg at the end of the
+		/// crowdloan. Proofs given to ingest_relay_contributions are verified against it from now
+		/// on, so they do not need to be built for the relay parent of the block including them
+		#[pallet::call_index(26)]
+		#[pallet::weight(T::WeightInfo::snapshot_relay_state_root())]
+		pub fn snapshot_relay_state_root(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			let root = T::RelayStateRoot::relay_state_root()
+				.ok_or(Error::<T>::RelayStateRootUnavailable)?;
+			RelayStateRootSnapshot::<T>::put(root);
+
+			Self::deposit_event(Event::RelayStateRootSnapshotTaken(root));
+			Ok(Default::default())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account ID that holds the Crowdloan's funds
+		pub fn account_id() -> T::AccountId {
+			PALLET_ID.into_account_truncating()
+		}
+		/// The Account Id's balance
+		pub fn pot() -> BalanceOf<T> {
+			T::RewardCurrency::free_balance(&Self::account_id())
+		}
+		/// The account ID that holds the funds for the bonuses
+		pub fn bonus_account_id() -> T::AccountId {
+			PALLET_ID.into_sub_account_truncating(b"bonus")
+		}
+		/// The balance of the bonus pot
+		pub fn bonus_pot() -> BalanceOf<T> {
+			T::RewardCurrency::free_balance(&Self::bonus_account_id())
+		}
+		/// Bonuses granted but not paid yet
+		pub fn outstanding_bonuses() -> BalanceOf<T> {
+			InitializedBonusAmount::<T>::get().saturating_sub(PaidBonusAmount::<T>::get())
+		}
+		/// Rewards the pot still owes to the contributors
+		pub fn outstanding_rewards() -> BalanceOf<T> {
+			InitializedRewardAmount::<T>::get()
+				.saturating_sub(PaidRewardAmount::<T>::get())
+				.saturating_sub(ForfeitedRewardAmount::<T>::get())
+		}
+		/// Funds in the pot beyond the outstanding rewards and the funds reserved for the
+		/// supplemental round
+		pub fn excess_funds() -> BalanceOf<T> {
+			let reserved = SupplementalRound::<T>::get()
+				.map(|round| round.funds.saturating_sub(round.rewards))
+				.unwrap_or_else(Zero::zero);
+			Self::pot()
+				.saturating_sub(Self::outstanding_rewards())
+				.saturating_sub(reserved)
+		}
+		/// Keep track of funds moved into or out of the pot
+		pub(crate) fn record_funding(
+			account: T::AccountId,
+			amount: BalanceOf<T>,
+			kind: FundingKind,
+		) {
+			let index = FundingHistoryCount::<T>::get();
+			FundingHistory::<T>::insert(
+				index,
+				FundingRecord {
+					block: frame_system::Pallet::<T>::block_number(),
+					account,
+					amount,
+					kind,
+				},
+			);
+			FundingHistoryCount::<T>::put(index.saturating_add(1));
+		}
+		/// Whether a reward entry was already initialized for the relay account
+		pub fn is_contribution_initialized(relay_account: &T::RelayChainAccountId) -> bool {
+			ClaimedRelayChainIds::<T>::get(relay_account).is_some()
+				|| UnassociatedContributions::<T>::get(relay_account).is_some()
+		}
+		/// Insert the reward of a relay account, associated with the native account if provided,
+		/// and make the initial payment to it. Returns the initial payment made.
+		/// New entries vest in the given window if provided, see RewardInfo::vesting_end. When the
+		/// reward is added to an existing entry, the entry vests in the combination of both windows
+		/// Callers need to check the contribution was not initialized already and to keep track
+		/// of InitializedRewardAmount and TotalContributors
+		pub(crate) fn insert_contribution(
+			relay_account: &T::RelayChainAccountId,
+			native_account: Option<&T::AccountId>,
+			reward: BalanceOf<T>,
+			contribution: Option<u128>,
+			early_bird: BalanceOf<T>,
+			vesting_window: Option<(T::VestingBlockNumber, T::VestingBlockNumber)>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			// If we have a native_account, we make the payment
+			let initial_payment = if let Some(native_account) = native_account {
+				Self::make_initial_payment(native_account, reward)?
+			} else {
+				0u32.into()
+			};
+
+			// Calculate the reward info to store after the initial payment has been made.
+			let mut reward_info = RewardInfo {
+				total_reward: reward,
+				claimed_reward: initial_payment,
+				contributed_relay_addresses: vec![relay_account.clone()],
+				vesting_start: None,
+				vesting_end: None,
+				contribution,
+				bonus: BonusInfo {
+					early_bird,
+					..Default::default()
+				},
+				redistribution: Default::default(),
+			};
+			if let Some((vesting_start, vesting_end)) = vesting_window {
+				reward_info.vesting_start = Some(vesting_start);
+				reward_info.vesting_end = Some(vesting_end);
+			}
+
+			// Keep track of the reward of each relay account, so that entries aggregating
+			// several relay accounts can be split later on
+			RelayRewards::<T>::insert(relay_account, reward);
+			if let Some(contribution) = contribution {
+				RelayContributions::<T>::insert(relay_account, contribution);
+			}
+
+			Statistics::<T>::mutate(|stats| {
+				stats.total_claimed = stats.total_claimed.saturating_add(initial_payment);
+				if native_account.is_some() {
+					stats.associated_rewards = stats.associated_rewards.saturating_add(reward);
+					stats.associated_relay_accounts =
+						stats.associated_relay_accounts.saturating_add(1);
+				} else {
+					stats.unassociated_rewards = stats.unassociated_rewards.saturating_add(reward);
+				}
+			});
+
+			if let Some(native_account) = native_account {
+				if let Some(mut inserted_reward_info) = AccountsPayable::<T>::get(native_account) {
+					inserted_reward_info
+						.contributed_relay_addresses
+						.append(&mut reward_info.contributed_relay_addresses);
+					let was_fully_claimed = Self::is_fully_claimed(&inserted_reward_info);
+					Self::settle_redistribution(&mut inserted_reward_info);
+					let shares_before = Self::redistribution_shares(&inserted_reward_info);
+					// A late reward with its own vesting window cannot be vested on the schedule
+					// of the existing entry
+					let (vesting_start, vesting_end) =
+						Self::combined_vesting_window(&inserted_reward_info, &reward_info);
+					// the native account has already some rewards in, we add the new ones
+					let merged_reward_info = RewardInfo {
+						total_reward: inserted_reward_info.total_reward + reward_info.total_reward,
+						claimed_reward: inserted_reward_info.claimed_reward
+							+ reward_info.claimed_reward,
+						contributed_relay_addresses: inserted_reward_info
+							.contributed_relay_addresses,
+						vesting_start,
+						vesting_end,
+						contribution: Self::combined_contribution(
+							inserted_reward_info.contribution,
+							reward_info.contribution,
+						),
+						bonus: Self::combined_bonus(inserted_reward_info.bonus, reward_info.bonus),
+						redistribution: inserted_reward_info.redistribution,
+					};
+					Self::update_fully_claimed_accounts(
+						was_fully_claimed as usize,
+						Self::is_fully_claimed(&merged_reward_info) as usize,
+					);
+					Self::update_redistribution_shares(
+						shares_before,
+						Self::redistribution_shares(&merged_reward_info),
+					);
+					AccountsPayable::<T>::insert(native_account, merged_reward_info);
+				} else {
+					// First reward association
+					if reward_info.vesting_end.is_none() {
+						reward_info.vesting_start =
+							Some(T::VestingBlockProvider::current_block_number());
+					}
+					Self::join_redistribution(&mut reward_info);
+					AccountsPayable::<T>::insert(native_account, reward_info);
+				}
+				ClaimedRelayChainIds::<T>::insert(relay_account, ());
+			} else {
+				UnassociatedContributions::<T>::insert(relay_account, reward_info);
+			}
+
+			Ok(initial_payment)
+		}
+		/// Pay the InitializationPayment of `reward` to `account`, returning the amount paid.
+		/// The payment is skipped if it would leave the account below the existential deposit,
+		/// in which case it can be claimed later along with the vested reward
+		fn make_initial_payment(
+			account: &T::AccountId,
+			reward: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let first_payment = T::InitializationPayment::get() * reward;
+			if !Self::can_receive(account, first_payment) {
+				Self::deposit_event(Event::InitialPaymentSkipped(account.clone(), first_payment));
+				return Ok(Zero::zero());
+			}
+			T::RewardCurrency::transfer(
+				&PALLET_ID.into_account_truncating(),
+				account,
+				first_payment,
+				AllowDeath,
+			)?;
+			Self::deposit_event(Event::InitialPaymentMade(account.clone(), first_payment));
+			T::OnRewardEvent::on_reward_paid(account, first_payment);
+			Ok(first_payment)
+		}
+		/// Whether `amount` can be paid into `dest` without leaving it below the existential
+		/// deposit
+		pub fn can_receive(dest: &T::AccountId, amount: BalanceOf<T>) -> bool {
+			amount.is_zero()
+				|| T::RewardCurrency::total_balance(dest).saturating_add(amount)
+					>= T::RewardCurrency::minimum_balance()
+		}
+		/// Whether `amount` can be transferred out of `source`, eg out of the pot
+		fn can_pay(source: &T::AccountId, amount: BalanceOf<T>) -> bool {
+			T::RewardCurrency::free_balance(source)
+				.checked_sub(&amount)
+				.map_or(false, |new_balance| {
+					T::RewardCurrency::ensure_can_withdraw(
+						source,
+						amount,
+						WithdrawReasons::TRANSFER,
+						new_balance,
+					)
+					.is_ok()
+				})
+		}
+		/// The reward paid for `reward` according to the reward curve
+		pub fn apply_reward_curve(
+			curve: &RewardCurveInfo<BalanceOf<T>>,
+			reward: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			// Unlike with Progressive, the part below the first threshold is paid in full
+			let below_tiers = curve
+				.tiers
+				.first()
+				.map_or(reward, |(threshold, _)| reward.min(*threshold));
+			let curved = below_tiers.saturating_add(crate::impls::apply_tiers(
+				reward,
+				&curve.tiers,
+				|rate, part| *rate * part,
+			));
+			curve.cap.map_or(curved, |cap| curved.min(cap))
+		}
+		/// The vesting window of an entry combining two entries. It starts at the latest of both
+		/// starts and ends at the latest of both ends
+		fn combined_vesting_window(
+			a: &RewardInfo<T>,
+			b: &RewardInfo<T>,
+		) -> (Option<T::VestingBlockNumber>, Option<T::VestingBlockNumber>) {
+			if a.vesting_end.is_some() || b.vesting_end.is_some() {
+				let (a_start, a_end) = Self::vesting_window(a);
+				let (b_start, b_end) = Self::vesting_window(b);
+				(Some(a_start.max(b_start)), Some(a_end.max(b_end)))
+			} else {
+				(a.vesting_start.clone().max(b.vesting_start.clone()), None)
+			}
+		}
+		/// The bonuses of an entry combining two entries. The loyalty bonus is settled if it was
+		/// for any of them, so that it cannot be paid twice
+		fn combined_bonus(
+			a: BonusInfo<BalanceOf<T>>,
+			b: BonusInfo<BalanceOf<T>>,
+		) -> BonusInfo<BalanceOf<T>> {
+			BonusInfo {
+				early_bird: a.early_bird.saturating_add(b.early_bird),
+				early_bird_paid: a.early_bird_paid.saturating_add(b.early_bird_paid),
+				loyalty_settled: a.loyalty_settled || b.loyalty_settled,
+			}
+		}
+		/// The contribution of an entry combining two entries, only known if both are known
+		fn combined_contribution(a: Option<u128>, b: Option<u128>) -> Option<u128> {
+			a.zip(b).map(|(a, b)| a.saturating_add(b))
+		}
+		/// The contribution of an entry with the given relay accounts, only known if the
+		/// contribution of every relay account is known
+		fn relay_accounts_contribution(relay_accounts: &[T::RelayChainAccountId]) -> Option<u128> {
+			relay_accounts
+				.iter()
+				.try_fold(0u128, |total, relay_account| {
+					RelayContributions::<T>::get(relay_account)
+						.map(|contribution| total.saturating_add(contribution))
+				})
+		}
+		/// The vesting status of a reward entry at the current vesting block
+		pub fn reward_entry_status(info: RewardInfo<T>) -> RewardEntryStatusOf<T> {
+			let now = T::VestingBlockProvider::current_block_number();
+			let (vesting_start, vesting_end) = Self::vesting_window(&info);
+			let vested_reward = Self::vested_reward(&info, now.clone());
+			let claimable_reward = Self::claimable_reward(&info, now);
+			RewardEntryStatus {
+				total_reward: info.total_reward,
+				claimed_reward: info.claimed_reward,
+				contributed_relay_addresses: info.contributed_relay_addresses,
+				vesting_start,
+				vesting_end,
+				vested_reward,
+				claimable_reward,
+			}
+		}
+		/// The vesting status of the reward entry of an account, if any
+		pub fn reward_entry(account: &T::AccountId) -> Option<RewardEntryStatusOf<T>> {
+			AccountsPayable::<T>::get(account).map(Self::reward_entry_status)
+		}
+		/// Whether a relay account contributed, and if so whether it is associated already
+		pub fn relay_account_status(
+			relay_account: &T::RelayChainAccountId,
+		) -> RelayAccountStatus<BalanceOf<T>> {
+			if let Some(reward_info) = UnassociatedContributions::<T>::get(relay_account) {
+				RelayAccountStatus::Unassociated(reward_info.total_reward)
+			} else if ClaimedRelayChainIds::<T>::contains_key(relay_account) {
+				RelayAccountStatus::Associated(RelayRewards::<T>::get(relay_account))
+			} else {
+				RelayAccountStatus::Unknown
+			}
+		}
+		/// Up to `limit` entries of AccountsPayable, starting after the `start_after` account.
+		/// The last account returned is the cursor of the next page
+		pub fn accounts_payable_page(
+			start_after: Option<T::AccountId>,
+			limit: u32,
+		) -> Vec<(T::AccountId, RewardEntryStatusOf<T>)> {
+			let iter = match start_after {
+				Some(cursor) => {
+					AccountsPayable::<T>::iter_from(AccountsPayable::<T>::hashed_key_for(cursor))
+				}
+				None => AccountsPayable::<T>::iter(),
+			};
+			iter.take(limit.min(MAX_PAGE_SIZE) as usize)
+				.map(|(account, info)| (account, Self::reward_entry_status(info)))
+				.collect()
+		}
+		/// Up to `limit` entries of UnassociatedContributions, starting after the `start_after`
+		/// relay account. The last relay account returned is the cursor of the next page
+		pub fn unassociated_contributions_page(
+			start_after: Option<T::RelayChainAccountId>,
+			limit: u32,
+		) -> Vec<(T::RelayChainAccountId, RewardEntryStatusOf<T>)> {
+			let iter = match start_after {
+				Some(cursor) => UnassociatedContributions::<T>::iter_from(
+					UnassociatedContributions::<T>::hashed_key_for(cursor),
+				),
+				None => UnassociatedContributions::<T>::iter(),
+			};
+			iter.take(limit.min(MAX_PAGE_SIZE) as usize)
+				.map(|(relay_account, info)| (relay_account, Self::reward_entry_status(info)))
+				.collect()
+		}
+		/// Whether every reward of the entry was claimed
+		pub fn is_fully_claimed(info: &RewardInfo<T>) -> bool {
+			info.claimed_reward >= info.total_reward
+		}
+		/// Keep track of the fully claimed accounts when entries are split or merged
+		fn update_fully_claimed_accounts(before: usize, after: usize) {
+			Statistics::<T>::mutate(|stats| {
+				stats.fully_claimed_accounts = stats
+					.fully_claimed_accounts
+					.saturating_sub(before as u32)
+					.saturating_add(after as u32);
+			});
+		}
+		/// The shares of an entry in the redistributed rewards: its total reward, until it is
+		/// fully claimed
+		pub fn redistribution_shares(info: &RewardInfo<T>) -> BalanceOf<T> {
+			if Self::is_fully_claimed(info) {
+				Zero::zero()
+			} else {
+				info.total_reward
+			}
+		}
+		/// Accrue the redistributed rewards owed to an entry since it was last settled. Needs to
+		/// be called before the shares of the entry change
+		fn settle_redistribution(info: &mut RewardInfo<T>) {
+			let index = RedistributionIndex::<T>::get();
+			let shares: u128 = Self::redistribution_shares(info).unique_saturated_into();
+			let owed = index
+				.saturating_sub(info.redistribution.index)
+				.saturating_mul_int(shares);
+			info.redistribution.pending = info
+				.redistribution
+				.pending
+				.saturating_add(BalanceOf::<T>::unique_saturated_from(owed));
+			info.redistribution.index = index;
+		}
+		/// A newly associated entry shares the rewards redistributed from now on
+		fn join_redistribution(info: &mut RewardInfo<T>) {
+			info.redistribution.index = RedistributionIndex::<T>::get();
+			Self::update_redistribution_shares(Zero::zero(), Self::redistribution_shares(info));
+		}
+		/// Keep track of the shares of every entry when they change
+		fn update_redistribution_shares(before: BalanceOf<T>, after: BalanceOf<T>) {
+			TotalRedistributionShares::<T>::mutate(|shares| {
+				*shares = shares.saturating_sub(before).saturating_add(after)
+			});
+		}
+		/// Share `amount`, already in the pot, among the associated entries not fully claimed
+		/// pro rata of their total reward. It is paid along with their next claim.
+		/// Returns the amount redistributed, which can be lower because of rounding. Whatever is
+		/// not redistributed stays in the pot as excess funds
+		pub fn redistribute(amount: BalanceOf<T>) -> BalanceOf<T> {
+			let total_shares: u128 = TotalRedistributionShares::<T>::get().unique_saturated_into();
+			if total_shares.is_zero() {
+				return Zero::zero();
+			}
+			let amount: u128 = amount.unique_saturated_into();
+			let increment = FixedU128::saturating_from_rational(amount, total_shares);
+			let redistributed =
+				BalanceOf::<T>::unique_saturated_from(increment.saturating_mul_int(total_shares));
+			if redistributed.is_zero() {
+				return Zero::zero();
+			}
+
+			RedistributionIndex::<T>::mutate(|index| *index = index.saturating_add(increment));
+			// The pot owes the redistributed rewards from now on
+			InitializedRewardAmount::<T>::mutate(|initialized| {
+				*initialized = initialized.saturating_add(redistributed)
+			});
+			Self::deposit_event(Event::RewardsRedistributed(redistributed));
+			redistributed
+		}
+		/// Pay a reward along with the redistributed rewards of an entry from the pot. Both are
+		/// paid in a single transfer, so only their sum needs to reach the existential deposit
+		fn pay_from_pot(
+			dest: &T::AccountId,
+			reward: BalanceOf<T>,
+			redistributed: BalanceOf<T>,
+		) -> DispatchResult {
+			T::RewardCurrency::transfer(
+				&Self::account_id(),
+				dest,
+				reward.saturating_add(redistributed),
+				AllowDeath,
+			)?;
+			if !redistributed.is_zero() {
+				Self::deposit_event(Event::RedistributedRewardsPaid(dest.clone(), redistributed));
+			}
+			Ok(())
+		}
+		/// Pay the early-bird and loyalty bonuses settled by settle_bonuses out of the bonus pot
+		fn pay_bonuses(
+			dest: &T::AccountId,
+			early_bird: BalanceOf<T>,
+			loyalty: BalanceOf<T>,
+		) -> DispatchResult {
+			let bonus = early_bird.saturating_add(loyalty);
+			if !bonus.is_zero() {
+				T::RewardCurrency::transfer(&Self::bonus_account_id(), dest, bonus, AllowDeath)?;
+			}
+			if !early_bird.is_zero() {
+				Self::deposit_event(Event::EarlyBirdBonusPaid(dest.clone(), early_bird));
+			}
+			if !loyalty.is_zero() {
+				Self::deposit_event(Event::LoyaltyBonusPaid(dest.clone(), loyalty));
+			}
+			Ok(())
+		}
+		/// The claim record follows the reward entry when its reward address changes
+		fn move_claim_record(previous_account: &T::AccountId, new_account: &T::AccountId) {
+			if let Some(record) = ClaimRecords::<T>::take(previous_account) {
+				ClaimRecords::<T>::insert(new_account, record);
+			}
+		}
+		/// Pay `amount` out of the currently vested reward of the payee into `dest`, or all of it
+		/// if not provided. Returns the amount paid, or None if the payout was deferred
+		pub(crate) fn do_claim(
+			payee: &T::AccountId,
+			dest: &T::AccountId,
+			amount: Option<BalanceOf<T>>,
+		) -> Result<Option<BalanceOf<T>>, DispatchError> {
+			let initialized = <Initialized<T>>::get();
+			ensure!(initialized, Error::<T>::RewardVecNotFullyInitializedYet);
+			// Calculate the veted amount on demand.
+			let mut info = AccountsPayable::<T>::get(payee).ok_or(Error::<T>::NoAssociatedClaim)?;
+			ensure!(
+				info.claimed_reward < info.total_reward,
+				Error::<T>::RewardsAlreadyClaimed
+			);
+
+			// Get the current block used for vesting purposes
+			let now = T::VestingBlockProvider::current_block_number();
+
+			let claimable_amount = Self::claimable_reward(&info, now.clone());
+			let payable_amount = match amount {
+				Some(amount) => {
+					ensure!(
+						amount <= claimable_amount,
+						Error::<T>::ClaimAmountBeyondVested
+					);
+					amount
+				}
+				None => claimable_amount,
+			};
+
+			// The redistributed rewards owed to the entry are paid in full
+			Self::settle_redistribution(&mut info);
+			let shares_before = Self::redistribution_shares(&info);
+			let redistributed = sp_std::mem::take(&mut info.redistribution.pending);
+
+			// Payouts that would leave dest below the existential deposit are deferred. They
+			// stay claimable and accumulate until they can be paid
+			if !Self::can_receive(dest, payable_amount.saturating_add(redistributed)) {
+				Self::deposit_event(Event::PayoutDeferred(
+					dest.clone(),
+					payable_amount.saturating_add(redistributed),
+				));
+				return Ok(None);
+			}
+
+			let claimed_before = info.claimed_reward;
+			info.claimed_reward = info.claimed_reward.saturating_add(payable_amount);
+			let (early_bird, loyalty) = Self::settle_bonuses(&mut info, claimed_before, now);
+			Self::update_redistribution_shares(shares_before, Self::redistribution_shares(&info));
+			AccountsPayable::<T>::insert(payee, &info);
+			PaidRewardAmount::<T>::mutate(|paid| {
+				*paid = paid
+					.saturating_add(payable_amount)
+					.saturating_add(redistributed)
+			});
+			Statistics::<T>::mutate(|stats| {
+				stats.total_claimed = stats
+					.total_claimed
+					.saturating_add(payable_amount)
+					.saturating_add(redistributed);
+				if Self::is_fully_claimed(&info) {
+					stats.fully_claimed_accounts = stats.fully_claimed_accounts.saturating_add(1);
+				}
+			});
+			ClaimRecords::<T>::mutate(payee, |record| {
+				let record = record.get_or_insert_with(Default::default);
+				record.last_claim_block = frame_system::Pallet::<T>::block_number();
+				record.claims = record.claims.saturating_add(1);
+			});
+
+			// This pallet controls an amount of funds and transfers them to each of the contributors
+			//TODO: contributors should have the balance locked for tranfers but not for democracy
+			Self::pay_from_pot(dest, payable_amount, redistributed)?;
+
+			Self::pay_bonuses(dest, early_bird, loyalty)?;
+			let bonus = early_bird.saturating_add(loyalty);
+			// The hook is told about everything dest received
+			T::OnRewardEvent::on_reward_paid(
+				dest,
+				payable_amount
+					.saturating_add(redistributed)
+					.saturating_add(bonus),
+			);
+			Ok(Some(payable_amount))
+		}
+		/// Account for the bonuses due after claiming, returning the early-bird and loyalty
+		/// bonuses to pay. The early-bird bonus follows the claimed reward, and the loyalty bonus
+		/// is decided in the first claim after vesting end: it is paid if no more than
+		/// LoyaltyThreshold of the vesting reward was claimed before (`claimed_before`) beyond the
+		/// initial payment
+		fn settle_bonuses(
+			info: &mut RewardInfo<T>,
+			claimed_before: BalanceOf<T>,
+			now: T::VestingBlockNumber,
+		) -> (BalanceOf<T>, BalanceOf<T>) {
+			// The early-bird bonus is paid in the same proportion as the reward
+			let early_bird_due = Perquintill::from_rational(info.claimed_reward, info.total_reward)
+				* info.bonus.early_bird;
+			let early_bird = early_bird_due.saturating_sub(info.bonus.early_bird_paid);
+			info.bonus.early_bird_paid = info.bonus.early_bird_paid.saturating_add(early_bird);
+
+			let mut loyalty = BalanceOf::<T>::zero();
+			let (_, vesting_end) = Self::vesting_window(info);
+			if !info.bonus.loyalty_settled && now >= vesting_end {
+				info.bonus.loyalty_settled = true;
+				// The initial payment was never a choice of the contributor
+				let first_paid = T::InitializationPayment::get() * info.total_reward;
+				let vesting_reward = info.total_reward.saturating_sub(first_paid);
+				if claimed_before.saturating_sub(first_paid)
+					<= T::LoyaltyThreshold::get() * vesting_reward
+				{
+					// Only what the bonus pot has beyond the outstanding bonuses can be paid
+					let available = Self::bonus_pot().saturating_sub(Self::outstanding_bonuses());
+					loyalty = (T::LoyaltyBonus::get() * info.total_reward).min(available);
+					InitializedBonusAmount::<T>::mutate(|granted| {
+						*granted = granted.saturating_add(loyalty)
+					});
+				}
+			}
+
+			PaidBonusAmount::<T>::mutate(|paid| {
+				*paid = paid.saturating_add(early_bird).saturating_add(loyalty)
+			});
+			(early_bird, loyalty)
+		}
+		/// Initialize the given reward entries, along with the contribution each reward was
+		/// converted from if any. See initialize_reward_vec
+		pub(crate) fn do_initialize_reward_vec(
+			entries: Vec<(InitializationEntryOf<T>, Option<u128>)>,
+		) -> DispatchResult {
+			let initialized = <Initialized<T>>::get();
+
+			// Once initialized, contributors can only be added in a supplemental round
+			let mut supplemental_round = if initialized {
+				Some(SupplementalRound::<T>::get().ok_or(Error::<T>::RewardVecAlreadyInitialized)?)
+			} else {
+				None
+			};
+
+			// Ensure we are below the max number of contributors
+			ensure!(
+				entries.len() as u32 <= T::MaxInitContributors::get(),
+				Error::<T>::TooManyContributors
+			);
+
+			// What is the amount initialized so far?
+			let mut current_initialized_rewards = InitializedRewardAmount::<T>::get();
+
+			// Total number of contributors
+			let mut total_contributors = TotalContributors::<T>::get();
+
+			// Apply the reward curve, keeping the requested rewards to report the reduction
+			let reward_curve = RewardCurve::<T>::get();
+			let entries: Vec<_> = entries
+				.into_iter()
+				.map(|(entry, contribution)| {
+					let reward = reward_curve.as_ref().map_or(entry.reward, |curve| {
+						Self::apply_reward_curve(curve, entry.reward)
+					});
+					(entry, reward, contribution)
+				})
+				.collect();
+
+			// Only the entries that are going to be initialized need to fit in the funds, so that
+			// a batch can be sent again once some of its entries were initialized
+			let mut seen = BTreeSet::new();
+			let new_entries: Vec<_> = entries
+				.iter()
+				.filter(|(entry, reward, _)| {
+					seen.insert(entry.relay_account.clone())
+						&& !Self::is_contribution_initialized(&entry.relay_account)
+						&& *reward >= T::MinimumReward::get()
+				})
+				.collect();
+			// Referral rewards are paid from the pot as well
+			let (incoming_rewards, incoming_bonuses) = new_entries.iter().fold(
+				(BalanceOf::<T>::zero(), BalanceOf::<T>::zero()),
+				|(rewards, bonuses), (entry, reward, _)| {
+					let referral_reward = entry
+						.referrer
+						.as_ref()
+						.map_or(Zero::zero(), |_| Self::referral_reward(*reward));
+					(
+						rewards + *reward + referral_reward,
+						bonuses + entry.early_bird_bonus * *reward,
+					)
+				},
+			);
+
+			// Ensure we dont go over funds, which are only the round funds in a supplemental round
+			if let Some(round) = &supplemental_round {
+				ensure!(
+					round.rewards + incoming_rewards <= round.funds,
+					Error::<T>::BatchBeyondFundPot
+				);
+			} else {
+				ensure!(
+					current_initialized_rewards + incoming_rewards <= Self::pot(),
+					Error::<T>::BatchBeyondFundPot
+				);
+			}
+			// Bonuses are only paid out of the bonus pot
+			ensure!(
+				Self::outstanding_bonuses() + incoming_bonuses <= Self::bonus_pot(),
+				Error::<T>::BatchBeyondBonusPot
+			);
+
+			let vesting_window = supplemental_round
+				.as_ref()
+				.and_then(|round| round.vesting_window.clone());
+
+			let mut initialized_bonuses = InitializedBonusAmount::<T>::get();
+			// Per-entry failures never abort the batch, they are counted for the summary event
+			let (mut inserted, mut skipped_duplicate, mut skipped_minimum, mut failed_payment) =
+				(0u32, 0u32, 0u32, 0u32);
+			let mut initialized_amount = BalanceOf::<T>::zero();
+			for (entry, reward, contribution) in &entries {
+				let InitializationEntry {
+					relay_account,
+					native_account,
+					reward: requested_reward,
+					early_bird_bonus,
+					referrer,
+				} = entry;
+
+				if Self::is_contribution_initialized(relay_account) {
+					// Dont fail as this is supposed to be called with batch calls and we
+					// dont want to stall the rest of the contributions
+					Self::deposit_event(Event::InitializedAlreadyInitializedAccount(
+						relay_account.clone(),
+						native_account.clone(),
+						*reward,
+					));
+					skipped_duplicate += 1;
 					continue;
 				}
 
+				if reward < requested_reward {
+					Self::deposit_event(Event::InitializedRewardReduced(
+						relay_account.clone(),
+						native_account.clone(),
+						*requested_reward,
+						*reward,
+					));
+				}
+
 				if *reward < T::MinimumReward::get() {
 					// Don't fail as this is supposed to be called with batch calls and we
 					// dont want to stall the rest of the contributions
@@ -526,80 +2474,354 @@ pub mod pallet {
 						native_account.clone(),
 						*reward,
 					));
+					skipped_minimum += 1;
 					continue;
 				}
 
-				// If we have a native_account, we make the payment
-				let initial_payment = if let Some(native_account) = native_account {
-					let first_payment = T::InitializationPayment::get() * (*reward);
-					T::RewardCurrency::transfer(
-						&PALLET_ID.into_account_truncating(),
-						&native_account,
-						first_payment,
-						AllowDeath,
-					)?;
-					Self::deposit_event(Event::InitialPaymentMade(
-						native_account.clone(),
-						first_payment,
-					));
-					first_payment
-				} else {
-					0u32.into()
-				};
-
-				// Calculate the reward info to store after the initial payment has been made.
-				let mut reward_info = RewardInfo {
-					total_reward: *reward,
-					claimed_reward: initial_payment,
-					contributed_relay_addresses: vec![relay_account.clone()],
+				let early_bird = *early_bird_bonus * *reward;
+				// Nothing is stored for the entry if its initial payment fails
+				let initial_payment = match with_storage_layer(|| {
+					Self::insert_contribution(
+						relay_account,
+						native_account.as_ref(),
+						*reward,
+						*contribution,
+						early_bird,
+						vesting_window.clone(),
+					)
+				}) {
+					Ok(initial_payment) => initial_payment,
+					Err(error) => {
+						// Don't fail, the entry can be initialized again in another batch
+						Self::deposit_event(Event::InitialPaymentFailed(
+							relay_account.clone(),
+							native_account.clone(),
+							*reward,
+							error,
+						));
+						failed_payment += 1;
+						continue;
+					}
 				};
 
+				inserted += 1;
+				initialized_amount += *reward;
 				current_initialized_rewards += *reward - initial_payment;
+				initialized_bonuses += early_bird;
 				total_contributors += 1;
+				if let Some(round) = &mut supplemental_round {
+					round.rewards += *reward;
+					round.contributors += 1;
+				}
 
-				if let Some(native_account) = native_account {
-					if let Some(mut inserted_reward_info) =
-						AccountsPayable::<T>::get(native_account)
-					{
-						inserted_reward_info
-							.contributed_relay_addresses
-							.append(&mut reward_info.contributed_relay_addresses);
-						// the native account has already some rewards in, we add the new ones
-						AccountsPayable::<T>::insert(
-							native_account,
-							RewardInfo {
-								total_reward: inserted_reward_info.total_reward
-									+ reward_info.total_reward,
-								claimed_reward: inserted_reward_info.claimed_reward
-									+ reward_info.claimed_reward,
-								contributed_relay_addresses: inserted_reward_info
-									.contributed_relay_addresses,
-							},
-						);
+				if let Some(referrer) = referrer {
+					let referral_reward = Self::referral_reward(*reward);
+					if Self::credit_referral(entry, referrer, referral_reward) {
+						current_initialized_rewards += referral_reward;
+						initialized_amount += referral_reward;
+						if let Some(round) = &mut supplemental_round {
+							round.rewards += referral_reward;
+						}
+						Self::deposit_event(Event::ReferralRewardCredited(
+							referrer.clone(),
+							referral_reward,
+						));
 					} else {
-						// First reward association
-						AccountsPayable::<T>::insert(native_account, reward_info);
+						// Don't fail, the contribution itself was initialized
+						Self::deposit_event(Event::ReferralRewardSkipped(
+							relay_account.clone(),
+							referrer.clone(),
+						));
 					}
-					ClaimedRelayChainIds::<T>::insert(relay_account, ());
-				} else {
-					UnassociatedContributions::<T>::insert(relay_account, reward_info);
 				}
 			}
 			InitializedRewardAmount::<T>::put(current_initialized_rewards);
+			InitializedBonusAmount::<T>::put(initialized_bonuses);
 			TotalContributors::<T>::put(total_contributors);
+			if let Some(round) = supplemental_round {
+				SupplementalRound::<T>::put(round);
+			}
 
-			Ok(Default::default())
+			Self::deposit_event(Event::InitializationBatchProcessed(
+				inserted,
+				skipped_duplicate,
+				skipped_minimum,
+				failed_payment,
+				initialized_amount,
+			));
+
+			Ok(())
 		}
-	}
+		/// The reward credited to a referrer for a contributor with the given reward
+		pub fn referral_reward(reward: BalanceOf<T>) -> BalanceOf<T> {
+			match T::ReferralReward::get() {
+				ReferralRewardKind::Percentage(percentage) => percentage * reward,
+				ReferralRewardKind::Flat(amount) => amount,
+			}
+		}
+		/// Add the referral reward to the entry of the referrer of `referee`, vesting along with
+		/// the rest of the entry. Returns false if the referrer has no entry or referred itself
+		fn credit_referral(
+			referee: &InitializationEntryOf<T>,
+			referrer: &ReferrerOf<T>,
+			referral_reward: BalanceOf<T>,
+		) -> bool {
+			match referrer {
+				Referrer::Native(account) => {
+					if referee.native_account.as_ref() == Some(account) {
+						return false;
+					}
+					AccountsPayable::<T>::mutate(account, |info| {
+						let Some(info) = info else {
+							return false;
+						};
+						let was_fully_claimed = Self::is_fully_claimed(info);
+						Self::settle_redistribution(info);
+						let shares_before = Self::redistribution_shares(info);
+						info.total_reward = info.total_reward.saturating_add(referral_reward);
+						Self::update_fully_claimed_accounts(
+							was_fully_claimed as usize,
+							Self::is_fully_claimed(info) as usize,
+						);
+						Self::update_redistribution_shares(
+							shares_before,
+							Self::redistribution_shares(info),
+						);
+						Statistics::<T>::mutate(|stats| {
+							stats.associated_rewards =
+								stats.associated_rewards.saturating_add(referral_reward)
+						});
+						true
+					})
+				}
+				Referrer::Relay(relay_account) => {
+					if relay_account == &referee.relay_account {
+						return false;
+					}
+					UnassociatedContributions::<T>::mutate(relay_account, |info| {
+						let Some(info) = info else {
+							return false;
+						};
+						info.total_reward = info.total_reward.saturating_add(referral_reward);
+						// The referral reward belongs to the relay account if the entry is split
+						RelayRewards::<T>::mutate(relay_account, |relay_reward| {
+							*relay_reward = Some(
+								relay_reward
+									.unwrap_or_default()
+									.saturating_add(referral_reward),
+							)
+						});
+						Statistics::<T>::mutate(|stats| {
+							stats.unassociated_rewards =
+								stats.unassociated_rewards.saturating_add(referral_reward)
+						});
+						true
+					})
+				}
+			}
+		}
+		/// Complete the initialization. See complete_initialization
+		pub(crate) fn do_complete_initialization(
+			lease_ending_block: T::VestingBlockNumber,
+			init_vesting_block: Option<T::VestingBlockNumber>,
+		) -> DispatchResult {
+			let initialized = <Initialized<T>>::get();
 
-	impl<T: Config> Pallet<T> {
-		/// The account ID that holds the Crowdloan's funds
-		pub fn account_id() -> T::AccountId {
-			PALLET_ID.into_account_truncating()
+			// This ensures there was no prior initialization
+			ensure!(
+				initialized == false,
+				Error::<T>::RewardVecAlreadyInitialized
+			);
+
+			// This ensures the end vesting block (when all funds are fully vested)
+			// is bigger than the init vesting block
+			ensure!(
+				lease_ending_block
+					> init_vesting_block
+						.clone()
+						.unwrap_or_else(InitVestingBlock::<T>::get),
+				Error::<T>::VestingPeriodNonValid
+			);
+
+			let current_initialized_rewards = InitializedRewardAmount::<T>::get();
+
+			let reward_difference = Self::pot().saturating_sub(current_initialized_rewards);
+
+			// Ensure the difference is not bigger than the total number of contributors
+			ensure!(
+				reward_difference < TotalContributors::<T>::get().into(),
+				Error::<T>::RewardsDoNotMatchFund
+			);
+
+			// Hand the difference over to the dust handler
+			let imbalance = T::RewardCurrency::withdraw(
+				&PALLET_ID.into_account_truncating(),
+				reward_difference,
+				WithdrawReasons::TRANSFER,
+				AllowDeath,
+			)?;
+			T::InitializationDust::on_unbalanced(imbalance);
+			if !reward_difference.is_zero() {
+				Self::deposit_event(Event::InitializationDustHandled(reward_difference));
+			}
+
+			if let Some(init_vesting_block) = init_vesting_block {
+				InitVestingBlock::<T>::put(init_vesting_block);
+			}
+			EndVestingBlock::<T>::put(lease_ending_block);
+
+			<Initialized<T>>::put(true);
+
+			Ok(())
 		}
-		/// The Account Id's balance
-		pub fn pot() -> BalanceOf<T> {
-			T::RewardCurrency::free_balance(&Self::account_id())
+		/// The vesting window of a reward entry
+		/// This is InitVestingBlock..EndVestingBlock, unless PerEntryVesting is set and the entry
+		/// was associated later, in which case the same period starts at the association.
+		/// Entries of a supplemental round with their own window vest in that window
+		pub fn vesting_window(
+			info: &RewardInfo<T>,
+		) -> (T::VestingBlockNumber, T::VestingBlockNumber) {
+			let init_vesting_block = InitVestingBlock::<T>::get();
+			let end_vesting_block = EndVestingBlock::<T>::get();
+			match (info.vesting_start.clone(), info.vesting_end.clone()) {
+				(vesting_start, Some(vesting_end)) => {
+					(vesting_start.unwrap_or(init_vesting_block), vesting_end)
+				}
+				(Some(vesting_start), None)
+					if T::PerEntryVesting::get() && vesting_start > init_vesting_block =>
+				{
+					let period = end_vesting_block.saturating_sub(init_vesting_block);
+					(vesting_start.clone(), vesting_start.saturating_add(period))
+				}
+				_ => (init_vesting_block, end_vesting_block),
+			}
+		}
+		/// How much of the reward of an entry is vested at the given block, including the
+		/// initialization payment
+		pub fn vested_reward(info: &RewardInfo<T>, now: T::VestingBlockNumber) -> BalanceOf<T> {
+			// Substract the first payment from the vested amount
+			let first_paid = T::InitializationPayment::get() * info.total_reward;
+			let vesting_reward = info.total_reward.saturating_sub(first_paid);
+
+			let (start, end) = Self::vesting_window(info);
+
+			// To calculate how much could the user have claimed already
+			let payable_period = now.saturating_sub(start.clone());
+
+			// How much should the contributor have already claimed by this block?
+			// By multiplying first we allow the conversion to integer done with the biggest number
+			let period = end.saturating_sub(start);
+			let should_have_claimed = if period == 0u32.into() {
+				// Pallet is configured with a zero vesting period.
+				vesting_reward
+			} else {
+				vesting_reward.saturating_mul(payable_period.into()) / period.into()
+			};
+
+			// If the period is bigger than whats missing to pay, then return whats missing to pay
+			if should_have_claimed >= vesting_reward {
+				info.total_reward
+			} else {
+				should_have_claimed + first_paid
+			}
+		}
+		/// How much of the reward of an entry is vested but not claimed yet at the given block
+		pub fn claimable_reward(info: &RewardInfo<T>, now: T::VestingBlockNumber) -> BalanceOf<T> {
+			Self::vested_reward(info, now).saturating_sub(info.claimed_reward)
+		}
+		/// Whether a claim of `who` is fee-free: there is a non-zero vested payout that is not
+		/// deferred and that the pots can pay, and the last claim was at least
+		/// FeelessClaimInterval blocks ago. Failed claims are rolled back along with their claim
+		/// record, so only claims that would succeed can be fee-free
+		pub fn is_feeless_claim(who: &T::AccountId) -> bool {
+			if !<Initialized<T>>::get() {
+				return false;
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			let rate_limited = ClaimRecords::<T>::get(who).map_or(false, |record| {
+				now < record
+					.last_claim_block
+					.saturating_add(T::FeelessClaimInterval::get())
+			});
+			!rate_limited
+				&& AccountsPayable::<T>::get(who).map_or(false, |mut info| {
+					let claimable = Self::claimable_reward(
+						&info,
+						T::VestingBlockProvider::current_block_number(),
+					);
+					// The redistributed rewards are paid along with the claim
+					Self::settle_redistribution(&mut info);
+					let payout = claimable.saturating_add(info.redistribution.pending);
+					// At most the rest of the early-bird bonus is paid from the bonus pot, the
+					// loyalty bonus being capped to what the bonus pot can pay
+					let early_bird = info
+						.bonus
+						.early_bird
+						.saturating_sub(info.bonus.early_bird_paid);
+					!claimable.is_zero()
+						&& Self::can_receive(who, payout)
+						&& Self::can_pay(&Self::account_id(), payout)
+						&& Self::can_pay(&Self::bonus_account_id(), early_bird)
+				})
+		}
+		/// Whether associating `reward_account` with `relay_account` is fee-free: the association
+		/// would succeed and make a non-zero initial payment, that is not skipped for being below
+		/// the existential deposit. Every relay account can only be associated once, which limits
+		/// the fee-free associations
+		pub fn is_feeless_association(
+			reward_account: &T::AccountId,
+			relay_account: &T::RelayChainAccountId,
+			proof: &MultiSignature,
+		) -> bool {
+			let Some(reward_info) = UnassociatedContributions::<T>::get(relay_account) else {
+				return false;
+			};
+			let initial_payment = T::InitializationPayment::get() * reward_info.total_reward;
+			!ClaimedRelayChainIds::<T>::contains_key(relay_account)
+				&& !AccountsPayable::<T>::contains_key(reward_account)
+				&& !initial_payment.is_zero()
+				&& Self::can_receive(reward_account, initial_payment)
+				&& Self::can_pay(&Self::account_id(), initial_payment)
+				&& Self::verify_signatures(
+					vec![(relay_account.clone(), proof.clone())],
+					reward_info,
+					Self::associate_native_identity_payload(reward_account),
+				)
+				.is_ok()
+		}
+		/// The payload that relay accounts need to sign to associate a native account
+		/// b"<Bytes>" + "SignatureNetworkIdentifier" + "reward_account" + b"</Bytes>"
+		pub fn associate_native_identity_payload(reward_account: &T::AccountId) -> Vec<u8> {
+			let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+			payload.append(&mut T::SignatureNetworkIdentifier::get().to_vec());
+			payload.append(&mut reward_account.encode());
+			payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+			payload
+		}
+		/// The payload that relay accounts need to sign to split a reward entry
+		/// b"<Bytes>" + "SignatureNetworkIdentifier" + b"split" + "account" + "splits" + b"</Bytes>"
+		pub fn split_reward_entry_payload(
+			account: &T::AccountId,
+			splits: &[(T::RelayChainAccountId, T::AccountId)],
+		) -> Vec<u8> {
+			let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+			payload.append(&mut T::SignatureNetworkIdentifier::get().to_vec());
+			payload.append(&mut b"split".to_vec());
+			payload.append(&mut account.encode());
+			payload.append(&mut splits.encode());
+			payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+			payload
+		}
+		/// The payload that relay accounts need to sign to merge two reward entries
+		/// b"<Bytes>" + "SignatureNetworkIdentifier" + b"merge" + "from" + "into" + b"</Bytes>"
+		pub fn merge_reward_entries_payload(from: &T::AccountId, into: &T::AccountId) -> Vec<u8> {
+			let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+			payload.append(&mut T::SignatureNetworkIdentifier::get().to_vec());
+			payload.append(&mut b"merge".to_vec());
+			payload.append(&mut from.encode());
+			payload.append(&mut into.encode());
+			payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+			payload
 		}
 		/// Verify a set of signatures made with relay chain accounts
 		/// We are verifying all the signatures, and then counting
@@ -688,18 +2910,62 @@ pub mod pallet {
 		NonContributedAddressProvided,
 		/// User submitted an unsifficient number of proofs to change the reward address
 		InsufficientNumberOfValidProofs,
+		/// The reward of the relay account is not known, so its entry cannot be split
+		UnknownRelayReward,
+		/// At least one relay account needs to remain in the entry being split
+		CannotSplitEntireEntry,
+		/// A reward entry cannot be merged with itself
+		CannotMergeWithItself,
+		/// The withdrawal would leave the pot without enough funds for the outstanding rewards
+		WithdrawalBeyondExcessFunds,
+		/// A supplemental round is already open
+		SupplementalRoundAlreadyOpen,
+		/// There is no supplemental round open
+		NoSupplementalRound,
+		/// The pot does not have enough funds beyond the outstanding rewards for the round
+		InsufficientFundsForSupplementalRound,
+		/// The amount to claim is bigger than the vested reward not claimed yet
+		ClaimAmountBeyondVested,
+		/// The relay state root is not available, or no snapshot of it was taken to verify relay
+		/// state proofs
+		RelayStateRootUnavailable,
+		/// The relay state proof does not prove the contribution of every relay account
+		InvalidRelayStateProof,
+		/// The thresholds of the reward curve do not increase, its rates do not diminish or it
+		/// has too many tiers
+		InvalidRewardCurve,
+		/// The bonus pot does not have enough funds for the early-bird bonuses of the batch
+		BatchBeyondBonusPot,
+		/// There are no entries to redistribute the funds to, or the amount is too small
+		NothingToRedistribute,
+		/// Unassociated entries can only be swept once their vesting period is over
+		SweepBeforeVestingEnd,
 	}
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		/// The amount of funds this pallet controls
 		pub funded_amount: BalanceOf<T>,
+		/// The vesting block height at which vesting starts. If not provided, it is set in the
+		/// first block when InitVestingAtFirstBlock is enabled
+		pub init_vesting_block: Option<T::VestingBlockNumber>,
+		/// The contributors to initialize, in the same format as initialize_reward_vec
+		pub rewards: Vec<(T::RelayChainAccountId, Option<T::AccountId>, BalanceOf<T>)>,
+		/// The vesting block height at which all rewards are vested. Only used if initialized
+		pub end_vesting_block: Option<T::VestingBlockNumber>,
+		/// Whether the initialization is completed at genesis, as complete_initialization would.
+		/// Both init_vesting_block and end_vesting_block are required then
+		pub initialized: bool,
 	}
 
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			Self {
 				funded_amount: 1u32.into(),
+				init_vesting_block: None,
+				rewards: vec![],
+				end_vesting_block: None,
+				initialized: false,
 			}
 		}
 	}
@@ -709,6 +2975,69 @@ pub mod pallet {
 		// This sets the funds of the crowdloan pallet
 		fn build(&self) {
 			T::RewardCurrency::deposit_creating(&Pallet::<T>::account_id(), self.funded_amount);
+			Pallet::<T>::record_funding(
+				Pallet::<T>::account_id(),
+				self.funded_amount,
+				FundingKind::Deposit,
+			);
+			if let Some(init_vesting_block) = self.init_vesting_block.clone() {
+				InitVestingBlock::<T>::put(init_vesting_block);
+			}
+
+			// The same checks as in initialize_reward_vec, but a wrong chain spec should not
+			// silently skip contributors
+			let incoming_rewards: BalanceOf<T> = self
+				.rewards
+				.iter()
+				.fold(0u32.into(), |acc: BalanceOf<T>, (_, _, reward)| {
+					acc + *reward
+				});
+			assert!(
+				incoming_rewards <= Pallet::<T>::pot(),
+				"Genesis rewards go beyond the funded amount"
+			);
+
+			let mut current_initialized_rewards: BalanceOf<T> = 0u32.into();
+			let mut total_contributors = 0u32;
+			for (relay_account, native_account, reward) in &self.rewards {
+				assert!(
+					!Pallet::<T>::is_contribution_initialized(relay_account),
+					"Duplicated relay account in genesis rewards"
+				);
+				assert!(
+					*reward >= T::MinimumReward::get(),
+					"Genesis reward below MinimumReward"
+				);
+				let initial_payment = Pallet::<T>::insert_contribution(
+					relay_account,
+					native_account.as_ref(),
+					*reward,
+					None,
+					0u32.into(),
+					None,
+				)
+				.expect("Genesis initial payment should succeed");
+
+				current_initialized_rewards += *reward - initial_payment;
+				total_contributors += 1;
+			}
+			InitializedRewardAmount::<T>::put(current_initialized_rewards);
+			TotalContributors::<T>::put(total_contributors);
+
+			if self.initialized {
+				// The vesting start cannot be left to the first block once initialized, as the
+				// end vesting block is validated against it
+				assert!(
+					self.init_vesting_block.is_some(),
+					"init_vesting_block is required to initialize at genesis"
+				);
+				let end_vesting_block = self
+					.end_vesting_block
+					.clone()
+					.expect("end_vesting_block is required to initialize at genesis");
+				Pallet::<T>::do_complete_initialization(end_vesting_block, None)
+					.expect("Genesis rewards should be valid to complete the initialization");
+			}
 		}
 	}
 
@@ -725,6 +3054,23 @@ pub mod pallet {
 	pub type UnassociatedContributions<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::RelayChainAccountId, RewardInfo<T>>;
 	#[pallet::storage]
+	#[pallet::getter(fn relay_rewards)]
+	/// Reward each relay account was initialized with. We store this to be able to split
+	/// entries that aggregate several relay accounts
+	pub type RelayRewards<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::RelayChainAccountId, BalanceOf<T>>;
+	#[pallet::storage]
+	#[pallet::getter(fn relay_contributions)]
+	/// Contribution each relay account was initialized with, if its reward was converted from it.
+	/// We store this to keep the contribution of split entries
+	pub type RelayContributions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::RelayChainAccountId, u128>;
+	#[pallet::storage]
+	#[pallet::getter(fn merge_approvals)]
+	/// Reward entries whose owner approved merging them with the entry of another account
+	pub type MergeApprovals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
+	#[pallet::storage]
 	#[pallet::getter(fn initialized)]
 	pub type Initialized<T: Config> = StorageValue<_, bool, ValueQuery, T::Initialized>;
 
@@ -746,11 +3092,80 @@ pub mod pallet {
 	/// check easier and more efficient
 	type InitializedRewardAmount<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn paid_reward_amount)]
+	/// Total amount paid to contributors out of InitializedRewardAmount, ie excluding the initial
+	/// payments made when initializing the rewards. Used to know the rewards still owed
+	pub(crate) type PaidRewardAmount<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn funding_history)]
+	/// Funds moved into or out of the pot, indexed by the order in which they happened
+	pub type FundingHistory<T: Config> = StorageMap<_, Twox64Concat, u32, FundingRecord<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn funding_history_count)]
+	/// Number of entries in FundingHistory
+	pub type FundingHistoryCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn statistics)]
+	/// Aggregate counters of the rewards
+	pub type Statistics<T: Config> = StorageValue<_, RewardStatistics<BalanceOf<T>>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn claim_records)]
+	/// When and how many times each account claimed its rewards
+	pub type ClaimRecords<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, ClaimRecord<BlockNumberFor<T>>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn supplemental_round)]
+	/// The supplemental round in which contributors can be initialized, if any
+	pub type SupplementalRound<T: Config> = StorageValue<_, SupplementalRoundInfo<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn init_bonus_amount)]
+	/// Early-bird and loyalty bonuses granted so far, paid from the bonus pot
+	pub type InitializedBonusAmount<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn paid_bonus_amount)]
+	/// Bonuses paid from the bonus pot so far
+	pub type PaidBonusAmount<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn forfeited_reward_amount)]
+	/// Rewards forfeited by exiting early, handed over to PenaltyDestination
+	pub type ForfeitedRewardAmount<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn redistribution_index)]
+	/// Rewards redistributed so far per share. Every associated entry not fully claimed has as
+	/// many shares as its total reward
+	pub type RedistributionIndex<T: Config> = StorageValue<_, FixedU128, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn total_redistribution_shares)]
+	/// Sum of the shares of every reward entry in the redistributed rewards
+	pub type TotalRedistributionShares<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn reward_curve)]
+	/// The curve applied to the rewards of the contributors being initialized, if any
+	pub type RewardCurve<T: Config> = StorageValue<_, RewardCurveInfo<BalanceOf<T>>>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn total_contributors)]
 	/// Total number of contributors to aid hinting benchmarking
 	type TotalContributors<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn relay_state_root_snapshot)]
+	/// The relay state root pinned by snapshot_relay_state_root, which proofs given to
+	/// ingest_relay_contributions are verified against
+	pub type RelayStateRootSnapshot<T: Config> = StorageValue<_, H256>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -776,5 +3191,109 @@ pub mod pallet {
 			Option<T::AccountId>,
 			BalanceOf<T>,
 		),
+		/// A reward entry has been split.
+		/// Data is the split account, the new account and the total reward of the new entry
+		RewardEntrySplit(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// The owner of a reward entry approved merging it with the entry of another account.
+		MergeApproved(T::AccountId, T::AccountId),
+		/// Two reward entries have been merged.
+		/// Data is the merged account, the receiving account and the total reward of the result
+		RewardEntriesMerged(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// The vesting block height at which vesting starts has been set.
+		InitVestingBlockSet(T::VestingBlockNumber),
+		/// The difference between the pot and the initialized rewards was handed over to the
+		/// InitializationDust handler.
+		InitializationDustHandled(BalanceOf<T>),
+		/// The pot has been funded. Data is the source account and the amount
+		PotFunded(T::AccountId, BalanceOf<T>),
+		/// Excess funds have been withdrawn from the pot.
+		/// Data is the destination account and the amount
+		ExcessWithdrawn(T::AccountId, BalanceOf<T>),
+		/// A payment made to an account was returned to the pot while resetting the campaign
+		InitialPaymentRefunded(T::AccountId, BalanceOf<T>),
+		/// Some reward entries were removed while resetting the campaign, but some are left.
+		/// Data is the number of entries removed
+		CampaignResetProgressed(u32),
+		/// The campaign has been reset. Data is the account receiving the pot and the amount sent
+		CampaignReset(T::AccountId, BalanceOf<T>),
+		/// A supplemental round has been opened. Data is the funds reserved for the round
+		SupplementalRoundOpened(BalanceOf<T>),
+		/// The supplemental round has been completed.
+		/// Data is the rewards and the number of contributors initialized in the round
+		SupplementalRoundCompleted(BalanceOf<T>, u32),
+		/// Claimed rewards have been staked through the StakingAdapter.
+		/// Data is the account and the amount staked
+		RewardsStaked(T::AccountId, BalanceOf<T>),
+		/// A contributor has claimed some rewards into another account.
+		/// Data is the contributor, the account getting paid and the amount of rewards paid.
+		RewardsPaidTo(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// The reward curve has been set, or removed if None.
+		RewardCurveSet(Option<RewardCurveInfo<BalanceOf<T>>>),
+		/// The reward of a contributor being initialized has been reduced by the reward curve.
+		/// Data is the relay account, the native account, the requested reward and the reward
+		/// after the curve.
+		InitializedRewardReduced(
+			T::RelayChainAccountId,
+			Option<T::AccountId>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		/// The bonus pot has been funded.
+		/// Data is the account the funds came from and the amount
+		BonusPotFunded(T::AccountId, BalanceOf<T>),
+		/// Early-bird bonus has been paid along with claimed rewards.
+		/// Data is the account getting paid and the amount
+		EarlyBirdBonusPaid(T::AccountId, BalanceOf<T>),
+		/// Loyalty bonus has been paid at vesting end.
+		/// Data is the account getting paid and the amount
+		LoyaltyBonusPaid(T::AccountId, BalanceOf<T>),
+		/// A referral reward has been credited to the entry of a referrer.
+		/// Data is the referrer and the amount, vesting along with the rest of the entry
+		ReferralRewardCredited(ReferrerOf<T>, BalanceOf<T>),
+		/// The referrer of an initialized contributor has no reward entry, or is the contributor
+		/// itself, so no referral reward was credited.
+		/// Data is the relay account of the contributor and the referrer
+		ReferralRewardSkipped(T::RelayChainAccountId, ReferrerOf<T>),
+		/// An account exited early, closing its reward entry.
+		/// Data is the account, the amount paid and the reward forfeited
+		ExitedEarly(T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// Funds have been redistributed among the entries not fully claimed.
+		/// Data is the amount redistributed
+		RewardsRedistributed(BalanceOf<T>),
+		/// The share of an entry in the redistributed rewards has been paid.
+		/// Data is the account getting paid and the amount
+		RedistributedRewardsPaid(T::AccountId, BalanceOf<T>),
+		/// The initial payment would have left the account below the existential deposit, so it
+		/// was skipped. It can be claimed later along with the vested reward.
+		/// Data is the account and the amount skipped
+		InitialPaymentSkipped(T::AccountId, BalanceOf<T>),
+		/// A payout would have left the account below the existential deposit, so it was
+		/// deferred until more rewards are vested.
+		/// Data is the account and the amount deferred
+		PayoutDeferred(T::AccountId, BalanceOf<T>),
+		/// When initializing the reward vec the initial payment of an entry failed, so the entry
+		/// was not initialized.
+		/// Data is the relay account, the native account, the reward and the payment error
+		InitialPaymentFailed(
+			T::RelayChainAccountId,
+			Option<T::AccountId>,
+			BalanceOf<T>,
+			DispatchError,
+		),
+		/// A batch of reward entries has been processed.
+		/// Data is the number of entries inserted, skipped as duplicates, skipped for being below
+		/// MinimumReward and failed because of their initial payment, and the rewards initialized,
+		/// including referral rewards
+		InitializationBatchProcessed(u32, u32, u32, u32, BalanceOf<T>),
+		/// The supplemental round has been closed without completing it.
+		/// Data is the rewards and the number of contributors initialized in the round, and the
+		/// funds released back to the excess funds
+		SupplementalRoundClosed(BalanceOf<T>, u32, BalanceOf<T>),
+		/// The rewards of unassociated entries have been swept.
+		/// Data is the number of entries swept and their rewards
+		UnassociatedRewardsSwept(u32, BalanceOf<T>),
+		/// The relay state root has been pinned for ingest_relay_contributions.
+		/// Data is the relay state root
+		RelayStateRootSnapshotTaken(H256),
 	}
 }
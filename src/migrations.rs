@@ -0,0 +1,154 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations for the crowdloan rewards pallet
+
+use crate::{
+	AccountsPayable, BalanceOf, Config, PaidRewardAmount, Pallet, RelayRewards, RewardInfo,
+	RewardStatistics, Statistics, TotalRedistributionShares, UnassociatedContributions,
+};
+use frame_support::{
+	migrations::VersionedMigration,
+	pallet_prelude::*,
+	traits::{Get, UncheckedOnRuntimeUpgrade},
+};
+use sp_runtime::traits::Saturating;
+use sp_std::vec::Vec;
+
+/// Migrate the pallet storage from v0 to v1, checking the on-chain storage version
+pub type MigrateV0ToV1<T> = VersionedMigration<
+	0,
+	1,
+	v1::UncheckedMigrateToV1<T>,
+	Pallet<T>,
+	<T as frame_system::Config>::DbWeight,
+>;
+
+pub mod v1 {
+	use super::*;
+
+	/// The reward info layout before per-entry vesting was introduced
+	#[derive(Decode)]
+	pub struct OldRewardInfo<T: Config> {
+		pub total_reward: BalanceOf<T>,
+		pub claimed_reward: BalanceOf<T>,
+		pub contributed_relay_addresses: Vec<T::RelayChainAccountId>,
+	}
+
+	impl<T: Config> From<OldRewardInfo<T>> for RewardInfo<T> {
+		fn from(old: OldRewardInfo<T>) -> Self {
+			RewardInfo {
+				total_reward: old.total_reward,
+				claimed_reward: old.claimed_reward,
+				contributed_relay_addresses: old.contributed_relay_addresses,
+				// Existing entries keep vesting from InitVestingBlock to EndVestingBlock
+				vesting_start: None,
+				vesting_end: None,
+				// Rewards were given directly
+				contribution: None,
+				bonus: Default::default(),
+				redistribution: Default::default(),
+			}
+		}
+	}
+
+	/// Translates every AccountsPayable and UnassociatedContributions entry to the new layout
+	/// and backfills RelayRewards for the entries with a single relay account. The reward of each
+	/// relay account of an entry aggregating several of them is not known, so those relay
+	/// accounts cannot be split out of their entry.
+	/// Do not use this directly, but through MigrateV0ToV1
+	pub struct UncheckedMigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut translated = 0u64;
+			let mut outstanding: BalanceOf<T> = 0u32.into();
+			let mut stats = RewardStatistics::<BalanceOf<T>>::default();
+			let mut redistribution_shares: BalanceOf<T> = 0u32.into();
+			let mut relay_rewards: Vec<(T::RelayChainAccountId, BalanceOf<T>)> = Vec::new();
+			AccountsPayable::<T>::translate::<OldRewardInfo<T>, _>(|_, old| {
+				translated += 1;
+				outstanding =
+					outstanding.saturating_add(old.total_reward.saturating_sub(old.claimed_reward));
+				stats.total_claimed = stats.total_claimed.saturating_add(old.claimed_reward);
+				stats.associated_rewards =
+					stats.associated_rewards.saturating_add(old.total_reward);
+				stats.associated_relay_accounts = stats
+					.associated_relay_accounts
+					.saturating_add(old.contributed_relay_addresses.len() as u32);
+				if old.claimed_reward >= old.total_reward {
+					stats.fully_claimed_accounts = stats.fully_claimed_accounts.saturating_add(1);
+				} else {
+					redistribution_shares = redistribution_shares.saturating_add(old.total_reward);
+				}
+				if let [relay_account] = old.contributed_relay_addresses.as_slice() {
+					relay_rewards.push((relay_account.clone(), old.total_reward));
+				}
+				Some(old.into())
+			});
+			UnassociatedContributions::<T>::translate::<OldRewardInfo<T>, _>(
+				|relay_account, old| {
+					translated += 1;
+					relay_rewards.push((relay_account.clone(), old.total_reward));
+					outstanding = outstanding
+						.saturating_add(old.total_reward.saturating_sub(old.claimed_reward));
+					stats.unassociated_rewards =
+						stats.unassociated_rewards.saturating_add(old.total_reward);
+					Some(old.into())
+				},
+			);
+			log::info!(target: "crowdloan-rewards", "Migrated {} reward entries", translated);
+
+			let backfilled = relay_rewards.len() as u64;
+			for (relay_account, reward) in relay_rewards {
+				RelayRewards::<T>::insert(relay_account, reward);
+			}
+
+			// Whatever is not owed anymore out of the initialized rewards was already paid
+			PaidRewardAmount::<T>::put(
+				Pallet::<T>::init_reward_amount().saturating_sub(outstanding),
+			);
+			// Past claims are not known, so there are no ClaimRecords for them
+			Statistics::<T>::put(stats);
+			TotalRedistributionShares::<T>::put(redistribution_shares);
+
+			T::DbWeight::get().reads_writes(
+				translated.saturating_add(1),
+				translated.saturating_add(backfilled).saturating_add(3),
+			)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+			let entries = (AccountsPayable::<T>::iter_keys().count()
+				+ UnassociatedContributions::<T>::iter_keys().count()) as u32;
+			Ok(entries.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			let expected = u32::decode(&mut &state[..])
+				.map_err(|_| "Failed to decode the number of reward entries")?;
+			let entries = (AccountsPayable::<T>::iter().count()
+				+ UnassociatedContributions::<T>::iter().count()) as u32;
+			ensure!(
+				entries == expected,
+				"Some reward entries could not be migrated"
+			);
+			Ok(())
+		}
+	}
+}
@@ -15,17 +15,23 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Test utilities
-use crate::{self as pallet_crowdloan_rewards, Config};
+use crate::{
+	self as pallet_crowdloan_rewards,
+	impls::{FixedRatio, LinearPenalty, ResolveToAccount},
+	traits::{OnRewardEvent, RelayStateRootProvider, StakingAdapter},
+	Config, ReferralRewardKind,
+};
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{ConstU32, Nothing, OnFinalize, OnInitialize},
+	traits::{ConstU32, Nothing, OnFinalize, OnInitialize, ReservableCurrency},
+	weights::Weight,
 };
 use frame_system::EnsureSigned;
 use sp_core::{ed25519, Pair, H256};
 use sp_io;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage, Perbill,
+	BuildStorage, FixedU128, Perbill,
 };
 use sp_std::convert::{From, TryInto};
 
@@ -81,7 +87,7 @@ impl frame_system::Config for Test {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u128 = 1;
+	pub static ExistentialDeposit: u128 = 1;
 }
 
 impl pallet_balances::Config for Test {
@@ -115,13 +121,70 @@ impl sp_runtime::traits::BlockNumberProvider for MockedBlockProvider {
 
 parameter_types! {
 	pub const TestMaxInitContributors: u32 = 8;
-	pub const TestMinimumReward: u128 = 0;
 	pub const TestInitialized: bool = false;
 	pub const TestInitializationPayment: Perbill = Perbill::from_percent(20);
 	pub const TestRewardAddressRelayVoteThreshold: Perbill = Perbill::from_percent(50);
 	pub const TestSigantureNetworkIdentifier: &'static [u8] = b"test-";
 }
 
+parameter_types! {
+	pub static TestMinimumReward: u128 = 0;
+	pub static TestPerEntryVesting: bool = false;
+	pub static TestInitVestingAtFirstBlock: bool = true;
+	pub const TreasuryAccount: u64 = 1000;
+	pub static RewardEvents: Vec<RewardEvent> = vec![];
+	pub static Staked: Vec<(u64, u64, Balance)> = vec![];
+	pub static TestRelayStateRoot: Option<H256> = None;
+	pub const TestCrowdloanFundIndex: u32 = 7;
+	pub const TestRewardRatio: FixedU128 = FixedU128::from_u32(2);
+	pub const TestLoyaltyBonus: Perbill = Perbill::from_percent(10);
+	pub static TestLoyaltyThreshold: Perbill = Perbill::from_percent(50);
+	pub static TestReferralReward: ReferralRewardKind<Balance> =
+		ReferralRewardKind::Percentage(Perbill::from_percent(5));
+	pub const TestEarlyExitPenalty: Perbill = Perbill::from_percent(50);
+	pub const PenaltyAccount: u64 = 1001;
+	pub const TestFeelessClaimInterval: u64 = 3;
+}
+
+/// Provides the relay state root set in TestRelayStateRoot
+pub struct MockRelayStateRoot;
+impl RelayStateRootProvider for MockRelayStateRoot {
+	fn relay_state_root() -> Option<H256> {
+		TestRelayStateRoot::get()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_relay_state_root(root: H256) {
+		TestRelayStateRoot::set(Some(root));
+	}
+}
+
+/// What the OnRewardEvent hooks were called with
+#[derive(Clone, Debug, PartialEq)]
+pub enum RewardEvent {
+	Paid(u64, Balance),
+	Associated([u8; 32], u64, Balance),
+	AddressUpdated(u64, u64),
+}
+
+/// Records every call to the OnRewardEvent hooks in RewardEvents
+pub struct RecordRewardEvents;
+impl OnRewardEvent<u64, [u8; 32], Balance> for RecordRewardEvents {
+	fn on_reward_paid(who: &u64, amount: Balance) {
+		RewardEvents::mutate(|events| events.push(RewardEvent::Paid(*who, amount)));
+	}
+
+	fn on_identity_associated(relay_account: &[u8; 32], who: &u64, total_reward: Balance) {
+		RewardEvents::mutate(|events| {
+			events.push(RewardEvent::Associated(*relay_account, *who, total_reward))
+		});
+	}
+
+	fn on_reward_address_updated(previous: &u64, new: &u64) {
+		RewardEvents::mutate(|events| events.push(RewardEvent::AddressUpdated(*previous, *new)));
+	}
+}
+
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Initialized = TestInitialized;
@@ -139,9 +202,43 @@ impl Config for Test {
 
 	type VestingBlockNumber = u64;
 	type VestingBlockProvider = MockedBlockProvider;
+	type PerEntryVesting = TestPerEntryVesting;
+	type InitVestingAtFirstBlock = TestInitVestingAtFirstBlock;
+	type InitializationDust = ResolveToAccount<Test, TreasuryAccount>;
+	type ResetPotDestination = TreasuryAccount;
+	type OnRewardEvent = RecordRewardEvents;
+	type StakingAdapter = ReserveStakingAdapter;
+	type RelayStateRoot = MockRelayStateRoot;
+	type CrowdloanFundIndex = TestCrowdloanFundIndex;
+	type RewardConversion = FixedRatio<TestRewardRatio>;
+	type LoyaltyBonus = TestLoyaltyBonus;
+	type LoyaltyThreshold = TestLoyaltyThreshold;
+	type ReferralReward = TestReferralReward;
+	type EarlyExitPenalty = LinearPenalty<TestEarlyExitPenalty>;
+	type PenaltyDestination = ResolveToAccount<Test, PenaltyAccount>;
+	type FeelessClaimInterval = TestFeelessClaimInterval;
 	type WeightInfo = ();
 }
 
+/// Stakes by reserving the funds, recording every stake in Staked. Target 0 is not a valid target
+pub struct ReserveStakingAdapter;
+impl StakingAdapter<u64, Balance> for ReserveStakingAdapter {
+	type Target = u64;
+
+	fn stake(who: &u64, target: u64, amount: Balance) -> sp_runtime::DispatchResult {
+		if target == 0 {
+			return Err(sp_runtime::DispatchError::Other("Invalid target"));
+		}
+		Balances::reserve(who, amount)?;
+		Staked::mutate(|staked| staked.push((*who, target, amount)));
+		Ok(())
+	}
+
+	fn weight() -> Weight {
+		Weight::zero()
+	}
+}
+
 impl pallet_utility::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
@@ -150,10 +247,19 @@ impl pallet_utility::Config for Test {
 }
 
 fn genesis(funded_amount: Balance) -> sp_io::TestExternalities {
+	genesis_with(pallet_crowdloan_rewards::GenesisConfig::<Test> {
+		funded_amount,
+		..Default::default()
+	})
+}
+
+pub(crate) fn genesis_with(
+	config: pallet_crowdloan_rewards::GenesisConfig<Test>,
+) -> sp_io::TestExternalities {
 	let mut storage = frame_system::GenesisConfig::<Test>::default()
 		.build_storage()
 		.unwrap();
-	pallet_crowdloan_rewards::GenesisConfig::<Test> { funded_amount }
+	config
 		.assimilate_storage(&mut storage)
 		.expect("Pallet balances storage can be assimilated");
 
@@ -0,0 +1,131 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reading crowdloan contributions out of a relay chain state proof
+//!
+//! The relay crowdloan pallet keeps the contributions to each fund in a child trie, keyed by the
+//! encoded contributor account, with `(contribution, memo)` as value.
+
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{crypto::AccountId32, storage::ChildInfo, H256};
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use sp_std::vec::Vec;
+use sp_trie::{read_trie_value, LayoutV1, MemoryDB, StorageProof};
+
+/// The child trie where the relay crowdloan pallet keeps the contributions to a fund
+pub fn crowdloan_child_info(fund_index: u32) -> ChildInfo {
+	let mut buf = b"crowdloan".to_vec();
+	buf.append(&mut fund_index.encode());
+	ChildInfo::new_default(BlakeTwo256::hash(&buf).as_ref())
+}
+
+/// Reasons why contributions cannot be read out of a relay state proof
+#[derive(Debug, PartialEq)]
+pub enum RelayStateProofError {
+	/// The proof does not contain the crowdloan child trie of the fund
+	FundNotFound,
+	/// The proof does not contain the contribution of an account
+	ContributionNotFound,
+	/// The proof is not valid against the relay state root
+	InvalidProof,
+	/// A proven value could not be decoded
+	InvalidValue,
+}
+
+/// Relay chain state proven against a relay state root
+pub struct RelayStateProof {
+	db: MemoryDB<BlakeTwo256>,
+	root: H256,
+}
+
+impl RelayStateProof {
+	pub fn new(root: H256, proof: Vec<Vec<u8>>) -> Self {
+		RelayStateProof {
+			db: StorageProof::new(proof).into_memory_db(),
+			root,
+		}
+	}
+
+	/// Read the contribution of every account to the fund, in the same order
+	pub fn read_contributions(
+		&self,
+		fund_index: u32,
+		accounts: &[AccountId32],
+	) -> Result<Vec<u128>, RelayStateProofError> {
+		let child_info = crowdloan_child_info(fund_index);
+		let child_root = self
+			.read(&self.root, &child_info.prefixed_storage_key().into_inner())?
+			.ok_or(RelayStateProofError::FundNotFound)?;
+		let child_root =
+			H256::decode(&mut &child_root[..]).map_err(|_| RelayStateProofError::InvalidValue)?;
+
+		accounts
+			.iter()
+			.map(|account| {
+				let value = self
+					.read(&child_root, &account.encode())?
+					.ok_or(RelayStateProofError::ContributionNotFound)?;
+				let (contribution, _memo) = <(u128, Vec<u8>)>::decode(&mut &value[..])
+					.map_err(|_| RelayStateProofError::InvalidValue)?;
+				Ok(contribution)
+			})
+			.collect()
+	}
+
+	fn read(&self, root: &H256, key: &[u8]) -> Result<Option<Vec<u8>>, RelayStateProofError> {
+		read_trie_value::<LayoutV1<BlakeTwo256>, _>(&self.db, root, key, None, None)
+			.map_err(|_| RelayStateProofError::InvalidProof)
+	}
+}
+
+/// Build a relay state root and a proof of the given contributions to the fund
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub fn build_relay_state_proof(
+	fund_index: u32,
+	contributions: &[(AccountId32, u128)],
+) -> (H256, Vec<Vec<u8>>) {
+	use sp_trie::{TrieDBMutBuilder, TrieMut};
+
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut child_root = H256::default();
+	{
+		let mut child_trie =
+			TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut child_root).build();
+		for (account, contribution) in contributions {
+			child_trie
+				.insert(
+					&account.encode(),
+					&(contribution, Vec::<u8>::new()).encode(),
+				)
+				.expect("Inserting in an in-memory trie does not fail");
+		}
+	}
+	let mut root = H256::default();
+	{
+		let mut trie = TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+		trie.insert(
+			&crowdloan_child_info(fund_index)
+				.prefixed_storage_key()
+				.into_inner(),
+			child_root.as_ref(),
+		)
+		.expect("Inserting in an in-memory trie does not fail");
+	}
+
+	// Every node of both tries is in the proof
+	let proof = db.drain().into_values().map(|(node, _)| node).collect();
+	(root, proof)
+}
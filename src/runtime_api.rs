@@ -0,0 +1,62 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API to query the crowdloan rewards pallet without decoding its storage
+
+use crate::{ClaimRecord, RelayAccountStatus, RewardEntryStatus, RewardStatistics};
+use parity_scale_codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Query the crowdloan rewards and their claims.
+	/// Runtimes implement it by reading the pallet getters
+	pub trait CrowdloanRewardsApi<AccountId, RelayChainAccountId, Balance, BlockNumber, VestingBlockNumber>
+	where
+		AccountId: Codec,
+		RelayChainAccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+		VestingBlockNumber: Codec,
+	{
+		/// Aggregate counters of the rewards
+		fn statistics() -> RewardStatistics<Balance>;
+
+		/// When and how many times the account claimed its rewards
+		fn claim_record(account: AccountId) -> Option<ClaimRecord<BlockNumber>>;
+
+		/// The reward entry of the account along with its vesting status, if any
+		fn reward_entry(
+			account: AccountId,
+		) -> Option<RewardEntryStatus<RelayChainAccountId, Balance, VestingBlockNumber>>;
+
+		/// Whether the relay account contributed, and if so whether it is associated already
+		fn relay_account_status(relay_account: RelayChainAccountId) -> RelayAccountStatus<Balance>;
+
+		/// Up to `limit` associated reward entries, starting after the `start_after` account.
+		/// Pass the last account returned to get the next page
+		fn accounts_payable(
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<(AccountId, RewardEntryStatus<RelayChainAccountId, Balance, VestingBlockNumber>)>;
+
+		/// Up to `limit` unassociated reward entries, starting after the `start_after` relay
+		/// account. Pass the last relay account returned to get the next page
+		fn unassociated_contributions(
+			start_after: Option<RelayChainAccountId>,
+			limit: u32,
+		) -> Vec<(RelayChainAccountId, RewardEntryStatus<RelayChainAccountId, Balance, VestingBlockNumber>)>;
+	}
+}
@@ -17,11 +17,15 @@
 //! Unit testing
 
 use crate::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::CheckIfFeeless,
+	traits::{Currency, LockableCurrency, OnUnbalanced, WithdrawReasons},
+};
 use mock::*;
 use parity_scale_codec::Encode;
 use sp_core::Pair;
-use sp_runtime::{traits::Dispatchable, DispatchError, ModuleError, MultiSignature};
+use sp_runtime::{traits::Dispatchable, DispatchError, ModuleError, MultiSignature, Perbill};
 
 // Constant that reflects the desired vesting period for the tests
 // Most tests complete initialization passing initRelayBlock + VESTING as the endRelayBlock
@@ -46,7 +50,8 @@ fn geneses() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 		assert_eq!(Crowdloan::total_contributors(), 5);
 
@@ -98,7 +103,8 @@ fn proving_assignation_works() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 		// 4 is not payable first
 		assert!(Crowdloan::accounts_payable(&3).is_none());
@@ -166,6 +172,7 @@ fn proving_assignation_works() {
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 100),
 			crate::Event::InitialPaymentMade(2, 100),
+			crate::Event::InitializationBatchProcessed(5, 0, 0, 0, 2500),
 			crate::Event::InitialPaymentMade(3, 100),
 			crate::Event::NativeIdentityAssociated(pairs[0].public().into(), 3, 500),
 		];
@@ -193,7 +200,8 @@ fn initializing_multi_relay_to_single_native_address_works() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 		// 1 is payable
 		assert!(Crowdloan::accounts_payable(&1).is_some());
@@ -215,6 +223,7 @@ fn initializing_multi_relay_to_single_native_address_works() {
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 100),
 			crate::Event::InitialPaymentMade(1, 100),
+			crate::Event::InitializationBatchProcessed(5, 0, 0, 0, 2500),
 			crate::Event::RewardsPaid(1, 200),
 		];
 		assert_eq!(events(), expected);
@@ -241,7 +250,8 @@ fn paying_works_step_by_step() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 		// 1 is payable
 		assert!(Crowdloan::accounts_payable(&1).is_some());
@@ -279,6 +289,7 @@ fn paying_works_step_by_step() {
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 100),
 			crate::Event::InitialPaymentMade(2, 100),
+			crate::Event::InitializationBatchProcessed(5, 0, 0, 0, 2500),
 			crate::Event::RewardsPaid(1, 100),
 			crate::Event::RewardsPaid(1, 50),
 			crate::Event::RewardsPaid(1, 50),
@@ -311,7 +322,8 @@ fn paying_works_after_unclaimed_period() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		// 1 is payable
@@ -344,6 +356,7 @@ fn paying_works_after_unclaimed_period() {
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 100),
 			crate::Event::InitialPaymentMade(2, 100),
+			crate::Event::InitializationBatchProcessed(5, 0, 0, 0, 2500),
 			crate::Event::RewardsPaid(1, 100),
 			crate::Event::RewardsPaid(1, 50),
 			crate::Event::RewardsPaid(1, 50),
@@ -378,7 +391,8 @@ fn paying_late_joiner_works() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		roll_to(12);
@@ -393,6 +407,7 @@ fn paying_late_joiner_works() {
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 100),
 			crate::Event::InitialPaymentMade(2, 100),
+			crate::Event::InitializationBatchProcessed(5, 0, 0, 0, 2500),
 			crate::Event::InitialPaymentMade(3, 100),
 			crate::Event::NativeIdentityAssociated(pairs[0].public().into(), 3, 500),
 			crate::Event::RewardsPaid(3, 400),
@@ -421,7 +436,8 @@ fn update_address_works() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		roll_to(4);
@@ -442,6 +458,7 @@ fn update_address_works() {
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 100),
 			crate::Event::InitialPaymentMade(2, 100),
+			crate::Event::InitializationBatchProcessed(5, 0, 0, 0, 2500),
 			crate::Event::RewardsPaid(1, 100),
 			crate::Event::RewardAddressUpdated(1, 8),
 			crate::Event::RewardsPaid(8, 100),
@@ -470,7 +487,8 @@ fn update_address_with_existing_address_fails() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		roll_to(4);
@@ -503,7 +521,8 @@ fn update_address_with_existing_with_multi_address_works() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		roll_to(4);
@@ -544,7 +563,8 @@ fn initialize_new_addresses() {
 		));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		assert_eq!(Crowdloan::initialized(), true);
@@ -559,7 +579,11 @@ fn initialize_new_addresses() {
 		);
 
 		assert_noop!(
-			Crowdloan::complete_initialization(RuntimeOrigin::root(), init_block + VESTING * 2),
+			Crowdloan::complete_initialization(
+				RuntimeOrigin::root(),
+				init_block + VESTING * 2,
+				None
+			),
 			Error::<Test>::RewardVecAlreadyInitialized,
 		);
 	});
@@ -587,15 +611,21 @@ fn initialize_new_addresses_handle_dust() {
 		let previous_issuance = Balances::total_issuance();
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
-		// We have burnt 1 unit
+		// We have sent 1 unit to the treasury
 		assert!(Crowdloan::pot() == crowdloan_pot - 1);
-		assert!(Balances::total_issuance() == previous_issuance - 1);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 1);
+		assert!(Balances::total_issuance() == previous_issuance);
 
 		assert_eq!(Crowdloan::initialized(), true);
 		assert_eq!(Balances::free_balance(10), 0);
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::InitializationDustHandled(1))
+		);
 	});
 }
 
@@ -618,7 +648,7 @@ fn initialize_new_addresses_not_matching_funds() {
 			]
 		));
 		assert_noop!(
-			Crowdloan::complete_initialization(RuntimeOrigin::root(), init_block + VESTING),
+			Crowdloan::complete_initialization(RuntimeOrigin::root(), init_block + VESTING, None),
 			Error::<Test>::RewardsDoNotMatchFund
 		);
 	});
@@ -643,7 +673,8 @@ fn initialize_new_addresses_with_batch() {
 		.dispatch(RuntimeOrigin::root()));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 		assert_eq!(Crowdloan::total_contributors(), 2);
 		// Verify that the second ending block provider had no effect
@@ -699,7 +730,8 @@ fn floating_point_arithmetic_works() {
 		.dispatch(RuntimeOrigin::root()));
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 		assert_eq!(Crowdloan::total_contributors(), 3);
 
@@ -738,8 +770,11 @@ fn floating_point_arithmetic_works() {
 		);
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 238),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 1190),
 			crate::Event::InitialPaymentMade(2, 237),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 1185),
 			crate::Event::InitialPaymentMade(3, 25),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 125),
 			crate::Event::RewardsPaid(3, 25),
 			crate::Event::RewardsPaid(3, 12),
 			crate::Event::RewardsPaid(3, 13),
@@ -772,7 +807,8 @@ fn reward_below_vesting_period_works() {
 
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		assert_eq!(
@@ -823,8 +859,11 @@ fn reward_below_vesting_period_works() {
 
 		let expected = vec![
 			crate::Event::InitialPaymentMade(1, 249),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 1247),
 			crate::Event::InitialPaymentMade(2, 249),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 1247),
 			crate::Event::InitialPaymentMade(3, 1),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 6),
 			crate::Event::RewardsPaid(3, 1),
 			crate::Event::RewardsPaid(3, 0),
 			crate::Event::RewardsPaid(3, 1),
@@ -885,7 +924,7 @@ fn test_initialization_errors() {
 
 		// Insert a non-valid vesting period
 		assert_noop!(
-			Crowdloan::complete_initialization(RuntimeOrigin::root(), init_block),
+			Crowdloan::complete_initialization(RuntimeOrigin::root(), init_block, None),
 			Error::<Test>::VestingPeriodNonValid
 		);
 
@@ -897,12 +936,13 @@ fn test_initialization_errors() {
 		// Complete
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		// Cannot initialize again
 		assert_noop!(
-			Crowdloan::complete_initialization(RuntimeOrigin::root(), init_block),
+			Crowdloan::complete_initialization(RuntimeOrigin::root(), init_block, None),
 			Error::<Test>::RewardVecAlreadyInitialized
 		);
 	});
@@ -933,7 +973,8 @@ fn test_relay_signatures_can_change_reward_addresses() {
 		// Complete
 		assert_ok!(Crowdloan::complete_initialization(
 			RuntimeOrigin::root(),
-			init_block + VESTING
+			init_block + VESTING,
+			None
 		));
 
 		let reward_info = Crowdloan::accounts_payable(&1).unwrap();
@@ -993,3 +1034,2752 @@ fn test_relay_signatures_can_change_reward_addresses() {
 		assert_eq!(reward_info, reward_info_2);
 	});
 }
+
+#[test]
+fn split_reward_entry_by_owner_works() {
+	empty().execute_with(|| {
+		let pairs = get_ed25519_pairs(2);
+		// The init relay block gets inserted
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), Some(1), 250u32.into()),
+				(pairs[0].public().into(), None, 625u32.into()),
+				(pairs[1].public().into(), None, 625u32.into())
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		assert_eq!(Crowdloan::relay_rewards(&[2u8; 32]), Some(250));
+
+		roll_to(4);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 500);
+
+		// The owner does not need to provide any proof
+		assert_ok!(Crowdloan::split_reward_entry(
+			RuntimeOrigin::signed(1),
+			1,
+			vec![([2u8; 32], 5)],
+			vec![]
+		));
+
+		// The claimed reward is split proportionally
+		let reward_info_1 = Crowdloan::accounts_payable(&1).unwrap();
+		assert_eq!(reward_info_1.total_reward, 1000);
+		assert_eq!(reward_info_1.claimed_reward, 400);
+		assert_eq!(reward_info_1.contributed_relay_addresses, vec![[1u8; 32]]);
+		let reward_info_5 = Crowdloan::accounts_payable(&5).unwrap();
+		assert_eq!(reward_info_5.total_reward, 250);
+		assert_eq!(reward_info_5.claimed_reward, 100);
+		assert_eq!(reward_info_5.contributed_relay_addresses, vec![[2u8; 32]]);
+
+		// Both entries keep vesting
+		roll_to(6);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(5)));
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 600);
+		assert_eq!(Crowdloan::accounts_payable(&5).unwrap().claimed_reward, 150);
+
+		let expected = vec![
+			crate::Event::InitialPaymentMade(1, 200),
+			crate::Event::InitialPaymentMade(1, 50),
+			crate::Event::InitializationBatchProcessed(4, 0, 0, 0, 2500),
+			crate::Event::RewardsPaid(1, 250),
+			crate::Event::RewardEntrySplit(1, 5, 250),
+			crate::Event::RewardsPaid(1, 200),
+			crate::Event::RewardsPaid(5, 50),
+		];
+		assert_eq!(events(), expected);
+	});
+}
+
+#[test]
+fn split_reward_entry_with_relay_keys_works() {
+	empty().execute_with(|| {
+		let pairs = get_ed25519_pairs(3);
+		// The init relay block gets inserted
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				(pairs[0].public().into(), Some(1), 500u32.into()),
+				(pairs[1].public().into(), Some(1), 500u32.into()),
+				(pairs[2].public().into(), Some(1), 500u32.into()),
+				([1u8; 32].into(), None, 500u32.into()),
+				([2u8; 32].into(), None, 500u32.into())
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		let splits: Vec<([u8; 32], u64)> = vec![(pairs[2].public().into(), 2)];
+		let payload = Crowdloan::split_reward_entry_payload(&1, &splits);
+
+		// Threshold is set to 50%, so one proof is not enough
+		let mut proofs: Vec<([u8; 32], MultiSignature)> =
+			vec![(pairs[0].public().into(), pairs[0].sign(&payload).into())];
+		assert_noop!(
+			Crowdloan::split_reward_entry(
+				RuntimeOrigin::signed(3),
+				1,
+				splits.clone(),
+				proofs.clone()
+			),
+			Error::<Test>::InsufficientNumberOfValidProofs
+		);
+
+		// With two proofs we should pass
+		proofs.push((pairs[1].public().into(), pairs[1].sign(&payload).into()));
+		assert_ok!(Crowdloan::split_reward_entry(
+			RuntimeOrigin::signed(3),
+			1,
+			splits,
+			proofs
+		));
+
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().total_reward, 1000);
+		assert_eq!(Crowdloan::accounts_payable(&2).unwrap().total_reward, 500);
+		assert_eq!(
+			Crowdloan::accounts_payable(&2)
+				.unwrap()
+				.contributed_relay_addresses,
+			vec![*pairs[2].public().as_array_ref()]
+		);
+	});
+}
+
+#[test]
+fn split_reward_entry_errors() {
+	empty().execute_with(|| {
+		let pairs = get_ed25519_pairs(1);
+		// The init relay block gets inserted
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), Some(1), 500u32.into()),
+				([3u8; 32].into(), Some(2), 500u32.into()),
+				(pairs[0].public().into(), None, 1000u32.into())
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// The new account cannot have an entry already
+		assert_noop!(
+			Crowdloan::split_reward_entry(
+				RuntimeOrigin::signed(1),
+				1,
+				vec![([2u8; 32], 2)],
+				vec![]
+			),
+			Error::<Test>::AlreadyAssociated
+		);
+
+		// The relay account needs to be part of the entry
+		assert_noop!(
+			Crowdloan::split_reward_entry(
+				RuntimeOrigin::signed(1),
+				1,
+				vec![([3u8; 32], 5)],
+				vec![]
+			),
+			Error::<Test>::NonContributedAddressProvided
+		);
+
+		// One relay account needs to remain
+		assert_noop!(
+			Crowdloan::split_reward_entry(
+				RuntimeOrigin::signed(1),
+				1,
+				vec![([1u8; 32], 5), ([2u8; 32], 6)],
+				vec![]
+			),
+			Error::<Test>::CannotSplitEntireEntry
+		);
+
+		// There must be an entry to split
+		assert_noop!(
+			Crowdloan::split_reward_entry(
+				RuntimeOrigin::signed(5),
+				5,
+				vec![([1u8; 32], 6)],
+				vec![]
+			),
+			Error::<Test>::NoAssociatedClaim
+		);
+	});
+}
+
+#[test]
+fn merge_reward_entries_approved_by_owners_works() {
+	empty().execute_with(|| {
+		let pairs = get_ed25519_pairs(3);
+		// The init relay block gets inserted
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				(pairs[0].public().into(), None, 500u32.into()),
+				(pairs[1].public().into(), None, 500u32.into()),
+				(pairs[2].public().into(), None, 500u32.into())
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		roll_to(4);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+
+		// The owner of 2 did not approve yet and no proofs are given
+		assert_noop!(
+			Crowdloan::merge_reward_entries(RuntimeOrigin::signed(1), 1, 2, vec![], vec![]),
+			Error::<Test>::InsufficientNumberOfValidProofs
+		);
+
+		// Once approved, the owner of 1 can merge
+		assert_ok!(Crowdloan::approve_merge(RuntimeOrigin::signed(2), Some(1)));
+		assert_eq!(Crowdloan::merge_approvals(&2), Some(1));
+		assert_ok!(Crowdloan::merge_reward_entries(
+			RuntimeOrigin::signed(1),
+			1,
+			2,
+			vec![],
+			vec![]
+		));
+
+		assert!(Crowdloan::accounts_payable(&1).is_none());
+		assert!(Crowdloan::merge_approvals(&2).is_none());
+		let reward_info = Crowdloan::accounts_payable(&2).unwrap();
+		assert_eq!(reward_info.total_reward, 1000);
+		assert_eq!(reward_info.claimed_reward, 300);
+		assert_eq!(
+			reward_info.contributed_relay_addresses,
+			vec![[2u8; 32], [1u8; 32]]
+		);
+
+		// The merged entry keeps vesting
+		roll_to(6);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(2)));
+		assert_eq!(Crowdloan::accounts_payable(&2).unwrap().claimed_reward, 600);
+
+		let expected = vec![
+			crate::Event::InitialPaymentMade(1, 100),
+			crate::Event::InitialPaymentMade(2, 100),
+			crate::Event::InitializationBatchProcessed(5, 0, 0, 0, 2500),
+			crate::Event::RewardsPaid(1, 100),
+			crate::Event::MergeApproved(2, 1),
+			crate::Event::RewardEntriesMerged(1, 2, 1000),
+			crate::Event::RewardsPaid(2, 300),
+		];
+		assert_eq!(events(), expected);
+	});
+}
+
+#[test]
+fn merge_reward_entries_with_relay_keys_works() {
+	empty().execute_with(|| {
+		let pairs = get_ed25519_pairs(4);
+		// The init relay block gets inserted
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				(pairs[0].public().into(), Some(1), 500u32.into()),
+				(pairs[1].public().into(), Some(1), 500u32.into()),
+				(pairs[2].public().into(), Some(2), 500u32.into()),
+				(pairs[3].public().into(), Some(2), 500u32.into()),
+				([1u8; 32].into(), None, 500u32.into())
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		let payload = Crowdloan::merge_reward_entries_payload(&1, &2);
+		let from_proofs: Vec<([u8; 32], MultiSignature)> =
+			vec![(pairs[0].public().into(), pairs[0].sign(&payload).into())];
+		let into_proofs: Vec<([u8; 32], MultiSignature)> =
+			vec![(pairs[2].public().into(), pairs[2].sign(&payload).into())];
+
+		// Relay keys of one entry cannot prove the other one
+		assert_noop!(
+			Crowdloan::merge_reward_entries(
+				RuntimeOrigin::signed(3),
+				1,
+				2,
+				from_proofs.clone(),
+				from_proofs.clone()
+			),
+			Error::<Test>::NonContributedAddressProvided
+		);
+
+		// A merge cannot target the same entry
+		assert_noop!(
+			Crowdloan::merge_reward_entries(RuntimeOrigin::signed(1), 1, 1, vec![], vec![]),
+			Error::<Test>::CannotMergeWithItself
+		);
+
+		assert_ok!(Crowdloan::merge_reward_entries(
+			RuntimeOrigin::signed(3),
+			1,
+			2,
+			from_proofs,
+			into_proofs
+		));
+
+		assert!(Crowdloan::accounts_payable(&1).is_none());
+		let reward_info = Crowdloan::accounts_payable(&2).unwrap();
+		assert_eq!(reward_info.total_reward, 2000);
+		assert_eq!(reward_info.claimed_reward, 400);
+		assert_eq!(reward_info.contributed_relay_addresses.len(), 4);
+	});
+}
+
+#[test]
+fn per_entry_vesting_starts_at_association() {
+	let pairs = get_ed25519_pairs(3);
+	let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+	payload.append(&mut TestSigantureNetworkIdentifier::get().to_vec());
+	payload.append(&mut 3u64.encode());
+	payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+	let signature: MultiSignature = pairs[0].sign(&payload).into();
+	empty().execute_with(|| {
+		TestPerEntryVesting::set(true);
+		// The init relay block gets inserted
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				(pairs[0].public().into(), None, 500u32.into()),
+				(pairs[1].public().into(), None, 500u32.into()),
+				(pairs[2].public().into(), None, 500u32.into())
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// Entries initialized before the vesting start use the global window
+		let reward_info_1 = Crowdloan::accounts_payable(&1).unwrap();
+		assert_eq!(
+			Crowdloan::vesting_window(&reward_info_1),
+			(init_block, init_block + VESTING)
+		);
+
+		// Vesting of the late joiner starts at association time
+		roll_to(12);
+		assert_ok!(Crowdloan::associate_native_identity(
+			RuntimeOrigin::signed(4),
+			3,
+			pairs[0].public().into(),
+			signature
+		));
+		let reward_info_3 = Crowdloan::accounts_payable(&3).unwrap();
+		assert_eq!(reward_info_3.vesting_start, Some(12));
+		assert_eq!(
+			Crowdloan::vesting_window(&reward_info_3),
+			(12, 12 + VESTING)
+		);
+
+		// Nothing but the initial payment is vested yet
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(3)));
+		assert_eq!(Crowdloan::accounts_payable(&3).unwrap().claimed_reward, 100);
+
+		roll_to(16);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(3)));
+		assert_eq!(Crowdloan::accounts_payable(&3).unwrap().claimed_reward, 300);
+
+		roll_to(20);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(3)));
+		assert_eq!(Crowdloan::accounts_payable(&3).unwrap().claimed_reward, 500);
+
+		// Entries vesting from the global start are fully vested
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 500);
+	});
+}
+
+#[test]
+fn vesting_start_is_ignored_without_per_entry_vesting() {
+	let pairs = get_ed25519_pairs(3);
+	let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+	payload.append(&mut TestSigantureNetworkIdentifier::get().to_vec());
+	payload.append(&mut 3u64.encode());
+	payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+	let signature: MultiSignature = pairs[0].sign(&payload).into();
+	empty().execute_with(|| {
+		// The init relay block gets inserted
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				(pairs[0].public().into(), None, 500u32.into()),
+				(pairs[1].public().into(), None, 500u32.into()),
+				(pairs[2].public().into(), None, 500u32.into())
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		roll_to(6);
+		assert_ok!(Crowdloan::associate_native_identity(
+			RuntimeOrigin::signed(4),
+			3,
+			pairs[0].public().into(),
+			signature
+		));
+
+		// The vesting start is recorded, but the global window applies
+		let reward_info_3 = Crowdloan::accounts_payable(&3).unwrap();
+		assert_eq!(reward_info_3.vesting_start, Some(6));
+		assert_eq!(
+			Crowdloan::vesting_window(&reward_info_3),
+			(init_block, init_block + VESTING)
+		);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(3)));
+		assert_eq!(Crowdloan::accounts_payable(&3).unwrap().claimed_reward, 300);
+	});
+}
+
+#[test]
+fn migration_to_v1_translates_reward_entries() {
+	use frame_support::{
+		storage::unhashed,
+		traits::{OnRuntimeUpgrade, StorageVersion},
+	};
+
+	empty().execute_with(|| {
+		StorageVersion::new(0).put::<Crowdloan>();
+
+		// Entries encoded with the v0 layout
+		let old_info = (500u128, 100u128, vec![[1u8; 32]]).encode();
+		unhashed::put_raw(&AccountsPayable::<Test>::hashed_key_for(&1), &old_info);
+		unhashed::put_raw(
+			&UnassociatedContributions::<Test>::hashed_key_for(&[2u8; 32]),
+			&old_info,
+		);
+		let old_aggregated_info = (1000u128, 0u128, vec![[3u8; 32], [4u8; 32]]).encode();
+		unhashed::put_raw(
+			&AccountsPayable::<Test>::hashed_key_for(&3),
+			&old_aggregated_info,
+		);
+
+		migrations::MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<Crowdloan>(), 1);
+		let expected = RewardInfo::<Test> {
+			total_reward: 500,
+			claimed_reward: 100,
+			contributed_relay_addresses: vec![[1u8; 32]],
+			vesting_start: None,
+			vesting_end: None,
+			contribution: None,
+			bonus: Default::default(),
+			redistribution: Default::default(),
+		};
+		assert_eq!(Crowdloan::accounts_payable(&1), Some(expected.clone()));
+		assert_eq!(
+			Crowdloan::unassociated_contributions(&[2u8; 32]),
+			Some(expected)
+		);
+		// Single relay entries can be split after the migration
+		assert_eq!(Crowdloan::relay_rewards(&[1u8; 32]), Some(500));
+		assert_eq!(Crowdloan::relay_rewards(&[2u8; 32]), Some(500));
+		// The reward of each relay account of an aggregated entry is not known
+		assert!(Crowdloan::relay_rewards(&[3u8; 32]).is_none());
+		assert_noop!(
+			Crowdloan::split_reward_entry(
+				RuntimeOrigin::signed(3),
+				3,
+				vec![([4u8; 32], 4)],
+				vec![]
+			),
+			Error::<Test>::UnknownRelayReward
+		);
+		assert_eq!(
+			Crowdloan::statistics(),
+			RewardStatistics {
+				total_claimed: 100,
+				associated_rewards: 1500,
+				unassociated_rewards: 500,
+				associated_relay_accounts: 3,
+				fully_claimed_accounts: 0,
+			}
+		);
+	});
+}
+
+#[test]
+fn complete_initialization_sets_vesting_start() {
+	empty().execute_with(|| {
+		TestInitVestingAtFirstBlock::set(false);
+		// The first block does not set the vesting start
+		roll_to(5);
+		assert_eq!(Crowdloan::init_vesting_block(), 0);
+
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), None, 2000u32.into())
+			]
+		));
+
+		// The end vesting block needs to be bigger than the provided start
+		assert_noop!(
+			Crowdloan::complete_initialization(RuntimeOrigin::root(), 12, Some(12)),
+			Error::<Test>::VestingPeriodNonValid
+		);
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			12 + VESTING,
+			Some(12)
+		));
+		assert_eq!(Crowdloan::init_vesting_block(), 12);
+		assert_eq!(Crowdloan::end_vesting_block(), 12 + VESTING);
+
+		// Nothing is vested before the start
+		roll_to(10);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 100);
+
+		roll_to(16);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 300);
+	});
+}
+
+#[test]
+fn set_init_vesting_block_works() {
+	empty().execute_with(|| {
+		assert_noop!(
+			Crowdloan::set_init_vesting_block(RuntimeOrigin::signed(1), 7),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Crowdloan::set_init_vesting_block(RuntimeOrigin::root(), 7));
+
+		// The first block does not overwrite an explicit vesting start
+		roll_to(2);
+		assert_eq!(Crowdloan::init_vesting_block(), 7);
+
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			7 + VESTING,
+			None
+		));
+
+		// It cannot be changed once initialized
+		assert_noop!(
+			Crowdloan::set_init_vesting_block(RuntimeOrigin::root(), 8),
+			Error::<Test>::RewardVecAlreadyInitialized
+		);
+
+		let expected = vec![
+			crate::Event::InitVestingBlockSet(7),
+			crate::Event::InitialPaymentMade(1, 500),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 2500),
+		];
+		assert_eq!(events(), expected);
+	});
+}
+
+#[test]
+fn genesis_sets_vesting_start() {
+	genesis_with(crate::GenesisConfig::<Test> {
+		funded_amount: 2500,
+		init_vesting_block: Some(5),
+		..Default::default()
+	})
+	.execute_with(|| {
+		roll_to(2);
+		assert_eq!(Crowdloan::init_vesting_block(), 5);
+	});
+}
+
+#[test]
+fn genesis_initializes_contributors() {
+	genesis_with(crate::GenesisConfig::<Test> {
+		funded_amount: 2500,
+		init_vesting_block: Some(2),
+		rewards: vec![
+			([1u8; 32], Some(1), 500),
+			([2u8; 32], Some(1), 500),
+			([3u8; 32], None, 1500),
+		],
+		end_vesting_block: Some(2 + VESTING),
+		initialized: true,
+	})
+	.execute_with(|| {
+		assert!(Crowdloan::initialized());
+		assert_eq!(Crowdloan::total_contributors(), 3);
+		assert_eq!(Crowdloan::init_reward_amount(), 2300);
+		assert_eq!(Crowdloan::end_vesting_block(), 2 + VESTING);
+
+		// Initial payments are made at genesis
+		assert_eq!(Balances::free_balance(1), 200);
+		let reward_info = Crowdloan::accounts_payable(&1).unwrap();
+		assert_eq!(reward_info.total_reward, 1000);
+		assert_eq!(reward_info.claimed_reward, 200);
+		assert_eq!(
+			reward_info.contributed_relay_addresses,
+			vec![[1u8; 32], [2u8; 32]]
+		);
+		assert!(Crowdloan::unassociated_contributions(&[3u8; 32]).is_some());
+
+		// No sudo call is needed to start claiming
+		roll_to(6);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 600);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Duplicated relay account in genesis rewards")]
+fn genesis_with_duplicated_contributors_fails() {
+	genesis_with(crate::GenesisConfig::<Test> {
+		funded_amount: 2500,
+		rewards: vec![([1u8; 32], Some(1), 500), ([1u8; 32], Some(2), 500)],
+		..Default::default()
+	});
+}
+
+#[test]
+#[should_panic(expected = "init_vesting_block is required to initialize at genesis")]
+fn genesis_initialized_without_vesting_start_fails() {
+	genesis_with(crate::GenesisConfig::<Test> {
+		funded_amount: 2500,
+		rewards: vec![([1u8; 32], Some(1), 500)],
+		end_vesting_block: Some(VESTING),
+		initialized: true,
+		..Default::default()
+	});
+}
+
+#[test]
+#[should_panic(expected = "Genesis rewards go beyond the funded amount")]
+fn genesis_beyond_funded_amount_fails() {
+	genesis_with(crate::GenesisConfig::<Test> {
+		funded_amount: 2500,
+		rewards: vec![([1u8; 32], Some(1), 2000), ([2u8; 32], Some(2), 501)],
+		..Default::default()
+	});
+}
+
+#[test]
+fn fund_pot_and_withdraw_excess_work() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1250u32.into()),
+				([2u8; 32].into(), Some(2), 1250u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// The pot holds exactly what is owed
+		assert_eq!(Crowdloan::outstanding_rewards(), Crowdloan::pot());
+		assert_eq!(Crowdloan::excess_funds(), 0);
+		assert_noop!(
+			Crowdloan::withdraw_excess(RuntimeOrigin::root(), 20, 1),
+			Error::<Test>::WithdrawalBeyondExcessFunds
+		);
+
+		Balances::make_free_balance_be(&10, 300);
+		assert_noop!(
+			Crowdloan::fund_pot(RuntimeOrigin::signed(10), 10, 200),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 200));
+		assert_eq!(Balances::free_balance(10), 100);
+		assert_eq!(Crowdloan::excess_funds(), 200);
+
+		// Claims reduce both the pot and what is owed
+		roll_to(4);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert!(Crowdloan::paid_reward_amount() > 0);
+		assert_eq!(Crowdloan::excess_funds(), 200);
+
+		assert_noop!(
+			Crowdloan::withdraw_excess(RuntimeOrigin::root(), 20, 201),
+			Error::<Test>::WithdrawalBeyondExcessFunds
+		);
+		assert_ok!(Crowdloan::withdraw_excess(RuntimeOrigin::root(), 20, 200));
+		assert_eq!(Balances::free_balance(20), 200);
+		assert_eq!(Crowdloan::outstanding_rewards(), Crowdloan::pot());
+
+		// Genesis funding, deposit and withdrawal
+		assert_eq!(Crowdloan::funding_history_count(), 3);
+		assert_eq!(
+			Crowdloan::funding_history(0).map(|record| (record.amount, record.kind)),
+			Some((2500, FundingKind::Deposit))
+		);
+		assert_eq!(
+			Crowdloan::funding_history(1),
+			Some(FundingRecord {
+				block: 1,
+				account: 10,
+				amount: 200,
+				kind: FundingKind::Deposit,
+			})
+		);
+		assert_eq!(
+			Crowdloan::funding_history(2),
+			Some(FundingRecord {
+				block: 3,
+				account: 20,
+				amount: 200,
+				kind: FundingKind::Withdrawal,
+			})
+		);
+
+		let expected = vec![
+			crate::Event::PotFunded(10, 200),
+			crate::Event::RewardsPaid(
+				1,
+				Crowdloan::accounts_payable(&1).unwrap().claimed_reward - 250,
+			),
+			crate::Event::ExcessWithdrawn(20, 200),
+		];
+		assert_eq!(events()[events().len() - 3..].to_vec(), expected);
+	});
+}
+
+#[test]
+fn reset_campaign_works() {
+	empty().execute_with(|| {
+		roll_to(2);
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				([3u8; 32].into(), None, 500u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::approve_merge(RuntimeOrigin::signed(1), Some(2)));
+		assert_eq!(Crowdloan::pot(), 2300);
+
+		// Leftovers of a previous run of the campaign
+		ClaimRecords::<Test>::insert(1, ClaimRecord::default());
+		ForfeitedRewardAmount::<Test>::put(10);
+		SupplementalRound::<Test>::put(SupplementalRoundInfo {
+			funds: 0,
+			rewards: 0,
+			contributors: 0,
+			vesting_window: None,
+		});
+
+		// Account 2 spent its initial payment
+		assert_ok!(Balances::transfer_allow_death(
+			RuntimeOrigin::signed(2),
+			20,
+			100
+		));
+
+		assert_noop!(
+			Crowdloan::reset_campaign(RuntimeOrigin::signed(1), 2),
+			DispatchError::BadOrigin
+		);
+
+		// First page
+		assert_ok!(Crowdloan::reset_campaign(RuntimeOrigin::root(), 2));
+		assert_eq!(Crowdloan::total_contributors(), 3);
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::CampaignResetProgressed(2))
+		);
+
+		// Last page
+		assert_ok!(Crowdloan::reset_campaign(RuntimeOrigin::root(), 2));
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::CampaignReset(TreasuryAccount::get(), 2399))
+		);
+		assert!(events().contains(&crate::Event::InitialPaymentRefunded(1, 99)));
+
+		// Only the initial payment of account 1 could be returned, keeping the account alive
+		assert_eq!(Balances::free_balance(1), 1);
+		assert_eq!(Balances::free_balance(20), 100);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 2399);
+		assert_eq!(Crowdloan::pot(), 0);
+
+		assert_eq!(Crowdloan::accounts_payable(&1), None);
+		assert_eq!(Crowdloan::accounts_payable(&2), None);
+		assert_eq!(Crowdloan::unassociated_contributions(&[3u8; 32]), None);
+		assert_eq!(Crowdloan::claimed_relay_chain_ids(&[1u8; 32]), None);
+		assert_eq!(Crowdloan::relay_rewards(&[2u8; 32]), None);
+		assert_eq!(Crowdloan::merge_approvals(&1), None);
+		assert_eq!(Crowdloan::claim_records(&1), None);
+		assert_eq!(Crowdloan::init_reward_amount(), 0);
+		assert_eq!(Crowdloan::forfeited_reward_amount(), 0);
+		assert_eq!(Crowdloan::supplemental_round(), None);
+		assert_eq!(Crowdloan::total_contributors(), 0);
+
+		// The campaign can start over
+		assert!(!Crowdloan::is_contribution_initialized(&[1u8; 32]));
+	});
+}
+
+#[test]
+fn reset_campaign_fails_after_initialization() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		assert_noop!(
+			Crowdloan::reset_campaign(RuntimeOrigin::root(), 10),
+			Error::<Test>::RewardVecAlreadyInitialized
+		);
+	});
+}
+
+#[test]
+fn supplemental_round_reuses_the_vesting_window() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1250u32.into()),
+				([2u8; 32].into(), Some(2), 1250u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// Fund the round
+		Balances::make_free_balance_be(&10, 1001);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 1000));
+		assert_ok!(Crowdloan::open_supplemental_round(
+			RuntimeOrigin::root(),
+			1000,
+			None
+		));
+		// The reserved funds cannot be withdrawn
+		assert_eq!(Crowdloan::excess_funds(), 0);
+
+		// Only the round funds are checked
+		assert_noop!(
+			Crowdloan::initialize_reward_vec(
+				RuntimeOrigin::root(),
+				vec![([3u8; 32].into(), Some(3), 1001u32.into())]
+			),
+			Error::<Test>::BatchBeyondFundPot
+		);
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([3u8; 32].into(), Some(3), 500u32.into()),
+				([4u8; 32].into(), None, 499u32.into()),
+			]
+		));
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Crowdloan::total_contributors(), 4);
+
+		assert_ok!(Crowdloan::complete_supplemental_round(RuntimeOrigin::root()));
+		assert_eq!(Crowdloan::supplemental_round(), None);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 1);
+		assert_eq!(Crowdloan::outstanding_rewards(), Crowdloan::pot());
+
+		// The late contributor vests in the global window
+		let reward_info = Crowdloan::accounts_payable(&3).unwrap();
+		assert_eq!(
+			Crowdloan::vesting_window(&reward_info),
+			(init_block, init_block + VESTING)
+		);
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 500);
+
+		// The round is closed
+		assert_noop!(
+			Crowdloan::initialize_reward_vec(
+				RuntimeOrigin::root(),
+				vec![([5u8; 32].into(), Some(5), 500u32.into())]
+			),
+			Error::<Test>::RewardVecAlreadyInitialized
+		);
+
+		let expected = vec![
+			crate::Event::InitializationDustHandled(1),
+			crate::Event::SupplementalRoundCompleted(999, 2),
+		];
+		assert!(events().ends_with(&[crate::Event::RewardsPaid(3, 400)]));
+		assert!(events()
+			.windows(2)
+			.any(|window| window == expected.as_slice()));
+	});
+}
+
+#[test]
+fn supplemental_round_with_its_own_vesting_window() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		Balances::make_free_balance_be(&10, 501);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 500));
+		assert_ok!(Crowdloan::open_supplemental_round(
+			RuntimeOrigin::root(),
+			500,
+			Some((20, 28))
+		));
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([3u8; 32].into(), Some(3), 500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_supplemental_round(RuntimeOrigin::root()));
+
+		let reward_info = Crowdloan::accounts_payable(&3).unwrap();
+		assert_eq!(reward_info.vesting_start, Some(20));
+		assert_eq!(reward_info.vesting_end, Some(28));
+		assert_eq!(Crowdloan::vesting_window(&reward_info), (20, 28));
+
+		// The global window is over, but not the one of the round
+		roll_to(24);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(3)));
+		assert_eq!(Crowdloan::accounts_payable(&3).unwrap().claimed_reward, 300);
+
+		roll_to(28);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(3)));
+		assert_eq!(Crowdloan::accounts_payable(&3).unwrap().claimed_reward, 500);
+	});
+}
+
+#[test]
+fn supplemental_round_rewards_added_to_an_entry_keep_their_vesting_window() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		roll_to(12);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(
+			Crowdloan::accounts_payable(&1).unwrap().claimed_reward,
+			2500
+		);
+
+		// A late reward for the same native account, vesting in the window of the round
+		Balances::make_free_balance_be(&10, 501);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 500));
+		assert_ok!(Crowdloan::open_supplemental_round(
+			RuntimeOrigin::root(),
+			500,
+			Some((20, 28))
+		));
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([3u8; 32].into(), Some(1), 500u32.into())]
+		));
+
+		// The merged entry vests until the end of the round window, not right away
+		let reward_info = Crowdloan::accounts_payable(&1).unwrap();
+		assert_eq!(reward_info.total_reward, 3000);
+		assert_eq!(reward_info.claimed_reward, 2600);
+		assert_eq!(Crowdloan::vesting_window(&reward_info), (20, 28));
+		assert_eq!(Crowdloan::claimable_reward(&reward_info, 12), 0);
+		assert_eq!(Crowdloan::claimable_reward(&reward_info, 27), 100);
+		assert_eq!(Crowdloan::claimable_reward(&reward_info, 28), 400);
+	});
+}
+
+#[test]
+fn supplemental_round_errors() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_noop!(
+			Crowdloan::open_supplemental_round(RuntimeOrigin::root(), 0, None),
+			Error::<Test>::RewardVecNotFullyInitializedYet
+		);
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		assert_noop!(
+			Crowdloan::complete_supplemental_round(RuntimeOrigin::root()),
+			Error::<Test>::NoSupplementalRound
+		);
+		assert_noop!(
+			Crowdloan::open_supplemental_round(RuntimeOrigin::signed(1), 0, None),
+			DispatchError::BadOrigin
+		);
+
+		// The funds need to be in the pot
+		assert_noop!(
+			Crowdloan::open_supplemental_round(RuntimeOrigin::root(), 100, None),
+			Error::<Test>::InsufficientFundsForSupplementalRound
+		);
+		Balances::make_free_balance_be(&10, 101);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 100));
+
+		assert_noop!(
+			Crowdloan::open_supplemental_round(RuntimeOrigin::root(), 100, Some((20, 20))),
+			Error::<Test>::VestingPeriodNonValid
+		);
+		assert_ok!(Crowdloan::open_supplemental_round(
+			RuntimeOrigin::root(),
+			100,
+			None
+		));
+		assert_noop!(
+			Crowdloan::open_supplemental_round(RuntimeOrigin::root(), 0, None),
+			Error::<Test>::SupplementalRoundAlreadyOpen
+		);
+
+		// The rewards of the round need to match its funds
+		assert_noop!(
+			Crowdloan::complete_supplemental_round(RuntimeOrigin::root()),
+			Error::<Test>::RewardsDoNotMatchFund
+		);
+	});
+}
+
+#[test]
+fn supplemental_round_can_be_closed_with_unused_funds() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		assert_noop!(
+			Crowdloan::close_supplemental_round(RuntimeOrigin::root()),
+			Error::<Test>::NoSupplementalRound
+		);
+
+		// The round only gets one of the planned contributors
+		Balances::make_free_balance_be(&10, 1001);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 1000));
+		assert_ok!(Crowdloan::open_supplemental_round(
+			RuntimeOrigin::root(),
+			1000,
+			None
+		));
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([2u8; 32].into(), Some(2), 400u32.into())]
+		));
+		assert_eq!(Crowdloan::excess_funds(), 0);
+		assert_noop!(
+			Crowdloan::complete_supplemental_round(RuntimeOrigin::root()),
+			Error::<Test>::RewardsDoNotMatchFund
+		);
+
+		assert_noop!(
+			Crowdloan::close_supplemental_round(RuntimeOrigin::signed(1)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Crowdloan::close_supplemental_round(RuntimeOrigin::root()));
+		assert!(Crowdloan::supplemental_round().is_none());
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::SupplementalRoundClosed(400, 1, 600))
+		);
+
+		// The contributor of the round keeps its reward, the rest can be withdrawn
+		assert_eq!(Crowdloan::accounts_payable(&2).unwrap().total_reward, 400);
+		assert_eq!(Crowdloan::excess_funds(), 600);
+		assert_ok!(Crowdloan::withdraw_excess(RuntimeOrigin::root(), 20, 600));
+
+		// Another round can be opened
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 1));
+		assert_ok!(Crowdloan::open_supplemental_round(
+			RuntimeOrigin::root(),
+			1,
+			None
+		));
+	});
+}
+
+#[test]
+fn reward_event_hooks_are_called() {
+	let pairs = get_ed25519_pairs(1);
+	let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+	payload.append(&mut TestSigantureNetworkIdentifier::get().to_vec());
+	payload.append(&mut 3u64.encode());
+	payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+	let signature: MultiSignature = pairs[0].sign(&payload).into();
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1250u32.into()),
+				(pairs[0].public().into(), None, 1250u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		assert_ok!(Crowdloan::associate_native_identity(
+			RuntimeOrigin::signed(4),
+			3,
+			pairs[0].public().into(),
+			signature
+		));
+		Balances::make_free_balance_be(&10, 300);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 250));
+		assert_ok!(Crowdloan::redistribute_excess(RuntimeOrigin::root(), 250));
+
+		// The account receiving the funds is reported, along with the redistributed rewards
+		roll_to(init_block + 4);
+		assert_ok!(Crowdloan::claim_to(RuntimeOrigin::signed(1), 6, Some(100)));
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::update_reward_address(
+			RuntimeOrigin::signed(1),
+			5
+		));
+
+		assert_eq!(
+			RewardEvents::get(),
+			vec![
+				RewardEvent::Paid(1, 250),
+				RewardEvent::Paid(3, 250),
+				RewardEvent::Associated(pairs[0].public().into(), 3, 1250),
+				RewardEvent::Paid(6, 100 + 125),
+				RewardEvent::Paid(1, 900),
+				RewardEvent::AddressUpdated(1, 5),
+			]
+		);
+	});
+}
+
+#[test]
+fn claim_and_stake_works() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_noop!(
+			Crowdloan::claim_and_stake(RuntimeOrigin::signed(1), 7),
+			Error::<Test>::RewardVecNotFullyInitializedYet
+		);
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		roll_to(init_block + VESTING / 2);
+		// Nothing is paid if staking fails
+		assert_noop!(
+			Crowdloan::claim_and_stake(RuntimeOrigin::signed(1), 0),
+			sp_runtime::DispatchError::Other("Invalid target")
+		);
+		assert_ok!(Crowdloan::claim_and_stake(RuntimeOrigin::signed(1), 7));
+
+		// Only the initial payment is left liquid
+		assert_eq!(Balances::free_balance(1), 500);
+		assert_eq!(Balances::reserved_balance(1), 1000);
+		assert_eq!(Staked::get(), vec![(1, 7, 1000)]);
+		assert_eq!(
+			Crowdloan::accounts_payable(&1).unwrap().claimed_reward,
+			1500
+		);
+
+		let expected = vec![
+			crate::Event::RewardsPaid(1, 1000),
+			crate::Event::RewardsStaked(1, 1000),
+		];
+		assert!(events().ends_with(&expected));
+	});
+}
+
+#[test]
+fn claim_to_works() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([1u8; 32].into(), Some(1), 2500u32.into())]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// 1000 are vested and not claimed
+		roll_to(init_block + VESTING / 2);
+		assert_noop!(
+			Crowdloan::claim_to(RuntimeOrigin::signed(1), 10, Some(1001)),
+			Error::<Test>::ClaimAmountBeyondVested
+		);
+		assert_noop!(
+			Crowdloan::claim_to(RuntimeOrigin::signed(2), 10, None),
+			Error::<Test>::NoAssociatedClaim
+		);
+
+		assert_ok!(Crowdloan::claim_to(RuntimeOrigin::signed(1), 10, Some(400)));
+		assert_eq!(Balances::free_balance(10), 400);
+		assert_eq!(Balances::free_balance(1), 500);
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 900);
+
+		// The rest of the vested reward
+		assert_ok!(Crowdloan::claim_to(RuntimeOrigin::signed(1), 11, None));
+		assert_eq!(Balances::free_balance(11), 600);
+		assert_eq!(
+			Crowdloan::accounts_payable(&1).unwrap().claimed_reward,
+			1500
+		);
+
+		// The reward address did not change
+		assert!(Crowdloan::accounts_payable(&10).is_none());
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+
+		let expected = vec![
+			crate::Event::RewardsPaidTo(1, 10, 400),
+			crate::Event::RewardsPaidTo(1, 11, 600),
+			crate::Event::RewardsPaid(1, 0),
+		];
+		assert!(events().ends_with(&expected));
+	});
+}
+
+#[test]
+fn statistics_and_claim_records_are_kept() {
+	let pairs = get_ed25519_pairs(1);
+	let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+	payload.append(&mut TestSigantureNetworkIdentifier::get().to_vec());
+	payload.append(&mut 3u64.encode());
+	payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+	let signature: MultiSignature = pairs[0].sign(&payload).into();
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1250u32.into()),
+				(pairs[0].public().into(), None, 1250u32.into()),
+			]
+		));
+		assert_eq!(
+			Crowdloan::statistics(),
+			RewardStatistics {
+				total_claimed: 250,
+				associated_rewards: 1250,
+				unassociated_rewards: 1250,
+				associated_relay_accounts: 1,
+				fully_claimed_accounts: 0,
+			}
+		);
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		assert_ok!(Crowdloan::associate_native_identity(
+			RuntimeOrigin::signed(4),
+			3,
+			pairs[0].public().into(),
+			signature
+		));
+
+		roll_to(init_block + VESTING / 2);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(
+			Crowdloan::claim_records(&1),
+			Some(ClaimRecord {
+				last_claim_block: init_block + VESTING - 1,
+				claims: 2,
+			})
+		);
+		assert_eq!(
+			Crowdloan::statistics(),
+			RewardStatistics {
+				total_claimed: 1500,
+				associated_rewards: 2500,
+				unassociated_rewards: 0,
+				associated_relay_accounts: 2,
+				fully_claimed_accounts: 1,
+			}
+		);
+
+		// The claim record follows the reward address
+		assert_ok!(Crowdloan::update_reward_address(
+			RuntimeOrigin::signed(1),
+			5
+		));
+		assert_eq!(Crowdloan::claim_records(&1), None);
+		assert_eq!(
+			Crowdloan::claim_records(&5).map(|record| record.claims),
+			Some(2)
+		);
+		assert_eq!(Crowdloan::claim_records(&3), None);
+	});
+}
+
+#[test]
+fn reward_entries_can_be_listed_in_pages() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				([3u8; 32].into(), Some(3), 500u32.into()),
+				([4u8; 32].into(), None, 500u32.into()),
+				([5u8; 32].into(), None, 500u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		roll_to(init_block + VESTING / 2);
+
+		// Walk the associated entries two by two
+		let mut accounts = vec![];
+		let mut cursor = None;
+		loop {
+			let page = Crowdloan::accounts_payable_page(cursor, 2);
+			assert!(page.len() <= 2);
+			match page.last() {
+				Some((account, _)) => cursor = Some(*account),
+				None => break,
+			}
+			accounts.extend(page);
+		}
+		accounts.sort_by_key(|(account, _)| *account);
+		assert_eq!(
+			accounts
+				.iter()
+				.map(|(account, _)| *account)
+				.collect::<Vec<_>>(),
+			vec![1, 2, 3]
+		);
+		assert_eq!(
+			accounts[0].1,
+			RewardEntryStatus {
+				total_reward: 500,
+				claimed_reward: 100,
+				contributed_relay_addresses: vec![[1u8; 32]],
+				vesting_start: init_block,
+				vesting_end: init_block + VESTING,
+				vested_reward: 300,
+				claimable_reward: 200,
+			}
+		);
+
+		let first_page = Crowdloan::unassociated_contributions_page(None, 1);
+		assert_eq!(first_page.len(), 1);
+		let second_page = Crowdloan::unassociated_contributions_page(Some(first_page[0].0), 10);
+		assert_eq!(second_page.len(), 1);
+		assert_ne!(first_page[0].0, second_page[0].0);
+		assert_eq!(second_page[0].1.claimed_reward, 0);
+		assert!(Crowdloan::unassociated_contributions_page(Some(second_page[0].0), 10).is_empty());
+	});
+}
+
+#[test]
+fn ingest_relay_contributions_works() {
+	let contributions = vec![
+		(sp_core::crypto::AccountId32::from([1u8; 32]), 100u128),
+		(sp_core::crypto::AccountId32::from([2u8; 32]), 200u128),
+	];
+	let (root, proof) =
+		relay_state::build_relay_state_proof(TestCrowdloanFundIndex::get(), &contributions);
+	empty().execute_with(|| {
+		TestRelayStateRoot::set(Some(root));
+		assert_ok!(Crowdloan::snapshot_relay_state_root(RuntimeOrigin::root()));
+		// Anyone can ingest the contributions, with the rewards given by the ratio
+		assert_ok!(Crowdloan::ingest_relay_contributions(
+			RuntimeOrigin::signed(9),
+			vec![[1u8; 32], [2u8; 32]],
+			proof.clone()
+		));
+		assert_eq!(
+			Crowdloan::unassociated_contributions(&[1u8; 32])
+				.unwrap()
+				.total_reward,
+			200
+		);
+		assert_eq!(
+			Crowdloan::unassociated_contributions(&[2u8; 32])
+				.unwrap()
+				.total_reward,
+			400
+		);
+		assert_eq!(Crowdloan::total_contributors(), 2);
+		assert_eq!(Crowdloan::init_reward_amount(), 600);
+
+		// Contributions are not ingested twice
+		assert_ok!(Crowdloan::ingest_relay_contributions(
+			RuntimeOrigin::signed(9),
+			vec![[1u8; 32]],
+			proof
+		));
+		assert_eq!(Crowdloan::init_reward_amount(), 600);
+		let expected = vec![
+			crate::Event::InitializedAlreadyInitializedAccount([1u8; 32], None, 200),
+			crate::Event::InitializationBatchProcessed(0, 1, 0, 0, 0),
+		];
+		assert!(events().ends_with(&expected));
+	});
+}
+
+#[test]
+fn ingest_relay_contributions_fails_without_a_valid_proof() {
+	let contributions = vec![(sp_core::crypto::AccountId32::from([1u8; 32]), 100u128)];
+	let (root, proof) =
+		relay_state::build_relay_state_proof(TestCrowdloanFundIndex::get(), &contributions);
+	let (other_fund_root, other_fund_proof) =
+		relay_state::build_relay_state_proof(TestCrowdloanFundIndex::get() + 1, &contributions);
+	empty().execute_with(|| {
+		assert_noop!(
+			Crowdloan::ingest_relay_contributions(
+				RuntimeOrigin::signed(9),
+				vec![[1u8; 32]],
+				proof.clone()
+			),
+			Error::<Test>::RelayStateRootUnavailable
+		);
+		// The snapshot needs a relay state root
+		assert_noop!(
+			Crowdloan::snapshot_relay_state_root(RuntimeOrigin::root()),
+			Error::<Test>::RelayStateRootUnavailable
+		);
+
+		TestRelayStateRoot::set(Some(root));
+		// Only the relay state root pinned by root is used
+		assert_noop!(
+			Crowdloan::ingest_relay_contributions(
+				RuntimeOrigin::signed(9),
+				vec![[1u8; 32]],
+				proof.clone()
+			),
+			Error::<Test>::RelayStateRootUnavailable
+		);
+		assert_noop!(
+			Crowdloan::snapshot_relay_state_root(RuntimeOrigin::signed(9)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Crowdloan::snapshot_relay_state_root(RuntimeOrigin::root()));
+		// The contribution is not in the proof
+		assert_noop!(
+			Crowdloan::ingest_relay_contributions(
+				RuntimeOrigin::signed(9),
+				vec![[2u8; 32]],
+				proof.clone()
+			),
+			Error::<Test>::InvalidRelayStateProof
+		);
+		// The proof is for another root
+		assert_noop!(
+			Crowdloan::ingest_relay_contributions(
+				RuntimeOrigin::signed(9),
+				vec![[1u8; 32]],
+				other_fund_proof
+			),
+			Error::<Test>::InvalidRelayStateProof
+		);
+		// The proof is not complete
+		assert_noop!(
+			Crowdloan::ingest_relay_contributions(
+				RuntimeOrigin::signed(9),
+				vec![[1u8; 32]],
+				proof[..1].to_vec()
+			),
+			Error::<Test>::InvalidRelayStateProof
+		);
+
+		// The root proves the contribution to another fund
+		TestRelayStateRoot::set(Some(other_fund_root));
+		assert_ok!(Crowdloan::snapshot_relay_state_root(RuntimeOrigin::root()));
+		assert_noop!(
+			Crowdloan::ingest_relay_contributions(RuntimeOrigin::signed(9), vec![[1u8; 32]], proof),
+			Error::<Test>::InvalidRelayStateProof
+		);
+	});
+}
+
+#[test]
+fn ingested_proofs_are_verified_against_the_snapshot() {
+	let contributions = vec![(sp_core::crypto::AccountId32::from([1u8; 32]), 100u128)];
+	let (root, proof) =
+		relay_state::build_relay_state_proof(TestCrowdloanFundIndex::get(), &contributions);
+	empty().execute_with(|| {
+		// The snapshot is taken at the end of the crowdloan, and the proof is built for it
+		TestRelayStateRoot::set(Some(root));
+		assert_ok!(Crowdloan::snapshot_relay_state_root(RuntimeOrigin::root()));
+		assert_eq!(Crowdloan::relay_state_root_snapshot(), Some(root));
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::RelayStateRootSnapshotTaken(root))
+		);
+
+		// The relay parent moved on by the time the call is included
+		TestRelayStateRoot::set(Some(sp_core::H256::repeat_byte(7)));
+		assert_ok!(Crowdloan::ingest_relay_contributions(
+			RuntimeOrigin::signed(9),
+			vec![[1u8; 32]],
+			proof
+		));
+		assert_eq!(
+			Crowdloan::unassociated_contributions(&[1u8; 32])
+				.unwrap()
+				.total_reward,
+			200
+		);
+	});
+}
+
+#[test]
+fn reward_conversions_work() {
+	use crate::impls::{Capped, DecimalAdjustedRatio, FixedRatio, Progressive};
+	use crate::traits::RewardConversion;
+	use sp_runtime::FixedU128;
+
+	frame_support::parameter_types! {
+		pub HalfRatio: FixedU128 = FixedU128::from_rational(1, 2);
+		pub const RelayDecimals: u32 = 10;
+		pub const NativeDecimals: u32 = 18;
+		pub const Cap: u128 = 300;
+		pub Tiers: Vec<(u128, FixedU128)> = vec![
+			(0, FixedU128::from_u32(2)),
+			(100, FixedU128::from_u32(1)),
+			(1000, FixedU128::from_rational(1, 10)),
+		];
+	}
+
+	assert_eq!(
+		<FixedRatio<HalfRatio> as RewardConversion<u128>>::convert(1001),
+		500
+	);
+	assert_eq!(
+		<DecimalAdjustedRatio<HalfRatio, RelayDecimals, NativeDecimals> as RewardConversion<
+			u128,
+		>>::convert(10u128.pow(10)),
+		10u128.pow(18) / 2
+	);
+	assert_eq!(
+		<DecimalAdjustedRatio<HalfRatio, NativeDecimals, RelayDecimals> as RewardConversion<
+			u128,
+		>>::convert(10u128.pow(18)),
+		10u128.pow(10) / 2
+	);
+	assert_eq!(
+		<Capped<Progressive<Tiers>, Cap> as RewardConversion<u128>>::convert(100),
+		200
+	);
+	assert_eq!(
+		<Capped<Progressive<Tiers>, Cap> as RewardConversion<u128>>::convert(1000),
+		300
+	);
+
+	// Every tier pays its own ratio
+	assert_eq!(
+		<Progressive<Tiers> as RewardConversion<u128>>::convert(50),
+		100
+	);
+	assert_eq!(
+		<Progressive<Tiers> as RewardConversion<u128>>::convert(500),
+		600
+	);
+	assert_eq!(
+		<Progressive<Tiers> as RewardConversion<u128>>::convert(3000),
+		1300
+	);
+}
+
+#[test]
+fn initialize_contributions_works() {
+	empty().execute_with(|| {
+		// Contributions are converted with a ratio of 2
+		assert_ok!(Crowdloan::initialize_contributions(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500),
+				([2u8; 32].into(), Some(1), 250),
+				([3u8; 32].into(), None, 100),
+			]
+		));
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([4u8; 32].into(), Some(2), 500)]
+		));
+
+		let reward_info = Crowdloan::accounts_payable(&1).unwrap();
+		assert_eq!(reward_info.total_reward, 1500);
+		assert_eq!(reward_info.contribution, Some(750));
+		assert_eq!(Crowdloan::relay_contributions([2u8; 32]), Some(250));
+		assert_eq!(
+			Crowdloan::unassociated_contributions(&[3u8; 32])
+				.unwrap()
+				.contribution,
+			Some(100)
+		);
+		assert_eq!(Crowdloan::accounts_payable(&2).unwrap().contribution, None);
+		assert_eq!(Crowdloan::init_reward_amount(), 2200 - 300 - 100);
+
+		// Split entries keep the contributions of their relay accounts
+		assert_ok!(Crowdloan::split_reward_entry(
+			RuntimeOrigin::signed(1),
+			1,
+			vec![([2u8; 32].into(), 3)],
+			vec![]
+		));
+		assert_eq!(
+			Crowdloan::accounts_payable(&1).unwrap().contribution,
+			Some(500)
+		);
+		assert_eq!(
+			Crowdloan::accounts_payable(&3).unwrap().contribution,
+			Some(250)
+		);
+
+		// Entries mixing converted and given rewards have no known contribution
+		assert_ok!(Crowdloan::approve_merge(RuntimeOrigin::signed(2), Some(1)));
+		assert_ok!(Crowdloan::merge_reward_entries(
+			RuntimeOrigin::signed(1),
+			1,
+			2,
+			vec![],
+			vec![]
+		));
+		assert_eq!(Crowdloan::accounts_payable(&2).unwrap().contribution, None);
+	});
+}
+
+#[test]
+fn reward_curve_is_applied_at_initialization() {
+	empty().execute_with(|| {
+		let curve = RewardCurveInfo {
+			tiers: vec![
+				(1000, Perbill::from_percent(50)),
+				(2000, Perbill::from_percent(10)),
+			],
+			cap: Some(1200),
+		};
+		assert_eq!(Crowdloan::apply_reward_curve(&curve, 800), 800);
+		assert_eq!(Crowdloan::apply_reward_curve(&curve, 1300), 1150);
+		assert_eq!(Crowdloan::apply_reward_curve(&curve, 5000), 1200);
+		assert_eq!(
+			Crowdloan::apply_reward_curve(
+				&RewardCurveInfo {
+					cap: None,
+					..curve.clone()
+				},
+				5000
+			),
+			1800
+		);
+
+		assert_ok!(Crowdloan::set_reward_curve(
+			RuntimeOrigin::root(),
+			Some(curve.clone())
+		));
+		// Only the curved rewards need to fit in the pot
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 800),
+				([2u8; 32].into(), Some(2), 5000),
+			]
+		));
+		// Converted contributions are curved too
+		assert_ok!(Crowdloan::initialize_contributions(
+			RuntimeOrigin::root(),
+			vec![([3u8; 32].into(), None, 250)]
+		));
+
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().total_reward, 800);
+		assert_eq!(Crowdloan::accounts_payable(&2).unwrap().total_reward, 1200);
+		assert_eq!(
+			Crowdloan::unassociated_contributions(&[3u8; 32])
+				.unwrap()
+				.total_reward,
+			500
+		);
+
+		let expected = vec![
+			crate::Event::RewardCurveSet(Some(curve)),
+			crate::Event::InitialPaymentMade(1, 160),
+			crate::Event::InitializedRewardReduced([2u8; 32], Some(2), 5000, 1200),
+			crate::Event::InitialPaymentMade(2, 240),
+			crate::Event::InitializationBatchProcessed(2, 0, 0, 0, 2000),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 500),
+		];
+		assert_eq!(events(), expected);
+
+		assert_ok!(Crowdloan::set_reward_curve(RuntimeOrigin::root(), None));
+		assert!(Crowdloan::reward_curve().is_none());
+	});
+}
+
+#[test]
+fn invalid_reward_curves_are_rejected() {
+	empty().execute_with(|| {
+		let curve = RewardCurveInfo {
+			tiers: vec![
+				(1000, Perbill::from_percent(50)),
+				(1000, Perbill::from_percent(10)),
+			],
+			cap: None,
+		};
+		assert_noop!(
+			Crowdloan::set_reward_curve(RuntimeOrigin::signed(1), Some(curve.clone())),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Crowdloan::set_reward_curve(RuntimeOrigin::root(), Some(curve)),
+			Error::<Test>::InvalidRewardCurve
+		);
+
+		let increasing_rates = RewardCurveInfo {
+			tiers: vec![
+				(1000, Perbill::from_percent(10)),
+				(2000, Perbill::from_percent(50)),
+			],
+			cap: None,
+		};
+		assert_noop!(
+			Crowdloan::set_reward_curve(RuntimeOrigin::root(), Some(increasing_rates)),
+			Error::<Test>::InvalidRewardCurve
+		);
+	});
+}
+
+#[test]
+fn early_bird_and_loyalty_bonuses_are_paid_from_the_bonus_pot() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		let entries = vec![
+			InitializationEntry {
+				relay_account: [1u8; 32],
+				native_account: Some(1),
+				reward: 1000,
+				early_bird_bonus: Perbill::from_percent(10),
+				referrer: None,
+			},
+			InitializationEntry {
+				relay_account: [2u8; 32],
+				native_account: Some(2),
+				reward: 1000,
+				early_bird_bonus: Perbill::from_percent(0),
+				referrer: None,
+			},
+		];
+
+		// Early-bird bonuses need to fit in the bonus pot
+		assert_noop!(
+			Crowdloan::initialize_reward_entries(RuntimeOrigin::root(), entries.clone()),
+			Error::<Test>::BatchBeyondBonusPot
+		);
+		Balances::make_free_balance_be(&10, 500);
+		assert_ok!(Crowdloan::fund_bonus_pot(RuntimeOrigin::root(), 10, 500));
+		assert_eq!(Crowdloan::bonus_pot(), 500);
+
+		assert_ok!(Crowdloan::initialize_reward_entries(
+			RuntimeOrigin::root(),
+			entries
+		));
+		// Bonuses are accounted apart from the rewards
+		assert_eq!(Crowdloan::init_bonus_amount(), 100);
+		assert_eq!(Crowdloan::init_reward_amount(), 1600);
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// 2 claims more than LoyaltyThreshold before vesting end
+		roll_to(init_block + 6);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(2)));
+
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(2)));
+
+		// 1 gets the early-bird bonus along with the reward and the loyalty bonus at vesting end
+		assert_eq!(Balances::free_balance(1), 1200);
+		assert_eq!(Balances::free_balance(2), 1000);
+		assert!(
+			Crowdloan::accounts_payable(&1)
+				.unwrap()
+				.bonus
+				.loyalty_settled
+		);
+		assert!(
+			Crowdloan::accounts_payable(&2)
+				.unwrap()
+				.bonus
+				.loyalty_settled
+		);
+		assert_eq!(Crowdloan::init_bonus_amount(), 200);
+		assert_eq!(Crowdloan::paid_bonus_amount(), 200);
+		assert_eq!(Crowdloan::bonus_pot(), 300);
+
+		let expected = vec![
+			crate::Event::EarlyBirdBonusPaid(1, 100),
+			crate::Event::LoyaltyBonusPaid(1, 100),
+			crate::Event::RewardsPaid(1, 800),
+		];
+		assert!(events()
+			.windows(3)
+			.any(|window| window == expected.as_slice()));
+		assert!(!events().iter().any(|event| matches!(
+			event,
+			crate::Event::EarlyBirdBonusPaid(2, _) | crate::Event::LoyaltyBonusPaid(2, _)
+		)));
+	});
+}
+
+#[test]
+fn loyalty_threshold_leaves_the_initial_payment_out() {
+	empty().execute_with(|| {
+		// Below the initial payment of 20%
+		TestLoyaltyThreshold::set(Perbill::from_percent(10));
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		Balances::make_free_balance_be(&10, 500);
+		assert_ok!(Crowdloan::fund_bonus_pot(RuntimeOrigin::root(), 10, 500));
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), Some(2), 1000u32.into()),
+				([3u8; 32].into(), None, 500u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// 2 claims 100 beyond its initial payment, 12.5% of its vesting reward
+		roll_to(init_block + 1);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(2)));
+
+		// 1 only got its initial payment before vesting end
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(2)));
+		assert_eq!(Balances::free_balance(1), 1100);
+		assert_eq!(Balances::free_balance(2), 1000);
+	});
+}
+
+#[test]
+fn referral_rewards_are_credited_to_the_referrer_entry() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		let entry = |relay: u8, native: Option<u64>, reward: u128, referrer| InitializationEntry {
+			relay_account: [relay; 32],
+			native_account: native,
+			reward,
+			early_bird_bonus: Perbill::from_percent(0),
+			referrer,
+		};
+		assert_ok!(Crowdloan::initialize_reward_entries(
+			RuntimeOrigin::root(),
+			vec![
+				entry(1, Some(1), 500, None),
+				entry(2, Some(2), 500, Some(Referrer::Native(1))),
+				// Unknown referrer
+				entry(3, None, 200, Some(Referrer::Relay([9u8; 32]))),
+				entry(4, None, 200, None),
+				entry(5, Some(5), 200, Some(Referrer::Relay([4u8; 32]))),
+				// Self-referral
+				entry(6, Some(6), 100, Some(Referrer::Native(6))),
+			]
+		));
+
+		// 5% of the reward of the referee, with no initial payment
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().total_reward, 525);
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 100);
+		assert_eq!(
+			Crowdloan::unassociated_contributions(&[4u8; 32])
+				.unwrap()
+				.total_reward,
+			210
+		);
+		assert_eq!(Crowdloan::relay_rewards(&[4u8; 32]), Some(210));
+		assert_eq!(Crowdloan::accounts_payable(&6).unwrap().total_reward, 100);
+		assert_eq!(Crowdloan::init_reward_amount(), 1475);
+		assert_eq!(Crowdloan::statistics().associated_rewards, 1325);
+		assert_eq!(Crowdloan::statistics().unassociated_rewards, 410);
+
+		let expected = vec![
+			crate::Event::ReferralRewardCredited(Referrer::Native(1), 25),
+			crate::Event::ReferralRewardSkipped([3u8; 32], Referrer::Relay([9u8; 32])),
+			crate::Event::ReferralRewardCredited(Referrer::Relay([4u8; 32]), 10),
+			crate::Event::ReferralRewardSkipped([6u8; 32], Referrer::Native(6)),
+		];
+		assert_eq!(
+			events()
+				.into_iter()
+				.filter(|event| matches!(
+					event,
+					crate::Event::ReferralRewardCredited(..)
+						| crate::Event::ReferralRewardSkipped(..)
+				))
+				.collect::<Vec<_>>(),
+			expected
+		);
+
+		// The referral reward vests along with the rest of the entry
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 525);
+	});
+}
+
+#[test]
+fn flat_referral_rewards_need_to_fit_in_the_pot() {
+	empty().execute_with(|| {
+		TestReferralReward::set(ReferralRewardKind::Flat(1000));
+		let entries = vec![
+			InitializationEntry {
+				relay_account: [1u8; 32],
+				native_account: Some(1),
+				reward: 1000,
+				early_bird_bonus: Perbill::from_percent(0),
+				referrer: None,
+			},
+			InitializationEntry {
+				relay_account: [2u8; 32],
+				native_account: Some(2),
+				reward: 1000,
+				early_bird_bonus: Perbill::from_percent(0),
+				referrer: Some(Referrer::Native(1)),
+			},
+		];
+		assert_noop!(
+			Crowdloan::initialize_reward_entries(RuntimeOrigin::root(), entries.clone()),
+			Error::<Test>::BatchBeyondFundPot
+		);
+
+		TestReferralReward::set(ReferralRewardKind::Flat(50));
+		assert_ok!(Crowdloan::initialize_reward_entries(
+			RuntimeOrigin::root(),
+			entries
+		));
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().total_reward, 1050);
+	});
+}
+
+#[test]
+fn exit_early_forfeits_part_of_the_unvested_reward() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		Balances::make_free_balance_be(&10, 100);
+		assert_ok!(Crowdloan::fund_bonus_pot(RuntimeOrigin::root(), 10, 100));
+		assert_ok!(Crowdloan::initialize_reward_entries(
+			RuntimeOrigin::root(),
+			vec![
+				InitializationEntry {
+					relay_account: [1u8; 32],
+					native_account: Some(1),
+					reward: 1000,
+					early_bird_bonus: Perbill::from_percent(10),
+					referrer: None,
+				},
+				InitializationEntry {
+					relay_account: [2u8; 32],
+					native_account: Some(2),
+					reward: 1500,
+					early_bird_bonus: Perbill::from_percent(0),
+					referrer: None,
+				},
+			]
+		));
+		assert_noop!(
+			Crowdloan::exit_early(RuntimeOrigin::signed(1)),
+			Error::<Test>::RewardVecNotFullyInitializedYet
+		);
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// Half of the vesting period elapsed, so half of the 50% penalty applies to the 400
+		// unvested tokens
+		roll_to(init_block + 4);
+		assert_ok!(Crowdloan::exit_early(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 200 + 700 + 90);
+		assert_eq!(Balances::free_balance(PenaltyAccount::get()), 100);
+		assert_eq!(Crowdloan::forfeited_reward_amount(), 100);
+		assert_eq!(Crowdloan::outstanding_rewards(), 1200);
+		assert_eq!(Crowdloan::pot(), 1200);
+
+		// The early-bird bonus of the forfeited reward is released
+		assert_eq!(Crowdloan::init_bonus_amount(), 90);
+		assert_eq!(Crowdloan::paid_bonus_amount(), 90);
+
+		// The entry is closed
+		let reward_info = Crowdloan::accounts_payable(&1).unwrap();
+		assert_eq!(reward_info.total_reward, 900);
+		assert_eq!(reward_info.claimed_reward, 900);
+		assert_eq!(Crowdloan::statistics().associated_rewards, 2400);
+		assert_eq!(Crowdloan::statistics().fully_claimed_accounts, 1);
+		assert_noop!(
+			Crowdloan::exit_early(RuntimeOrigin::signed(1)),
+			Error::<Test>::RewardsAlreadyClaimed
+		);
+		assert_noop!(
+			Crowdloan::claim(RuntimeOrigin::signed(1)),
+			Error::<Test>::RewardsAlreadyClaimed
+		);
+		assert_noop!(
+			Crowdloan::split_reward_entry(
+				RuntimeOrigin::signed(1),
+				1,
+				vec![([1u8; 32], 3)],
+				vec![]
+			),
+			Error::<Test>::RewardsAlreadyClaimed
+		);
+
+		// Nothing is forfeited once fully vested
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::exit_early(RuntimeOrigin::signed(2)));
+		assert_eq!(Balances::free_balance(2), 1500);
+		assert_eq!(Crowdloan::forfeited_reward_amount(), 100);
+
+		let expected = vec![
+			crate::Event::EarlyBirdBonusPaid(1, 90),
+			crate::Event::ExitedEarly(1, 700, 100),
+		];
+		assert!(events()
+			.windows(2)
+			.any(|window| window == expected.as_slice()));
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::ExitedEarly(2, 1200, 0))
+		);
+	});
+}
+
+#[test]
+fn redistributed_rewards_are_shared_among_vesting_entries() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				([3u8; 32].into(), None, 1000u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		// Unassociated entries do not share the redistributed rewards
+		assert_eq!(Crowdloan::total_redistribution_shares(), 1500);
+
+		// Only funds beyond the outstanding rewards can be redistributed
+		assert_noop!(
+			Crowdloan::redistribute_excess(RuntimeOrigin::root(), 300),
+			Error::<Test>::WithdrawalBeyondExcessFunds
+		);
+		Balances::make_free_balance_be(&10, 300);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 300));
+		assert_noop!(
+			Crowdloan::redistribute_excess(RuntimeOrigin::signed(1), 300),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Crowdloan::redistribute_excess(RuntimeOrigin::root(), 300));
+		assert_eq!(Crowdloan::excess_funds(), 0);
+
+		// The share of 1 is paid in full along with its vested reward
+		roll_to(init_block + 4);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 200 + 400 + 200);
+		assert_eq!(Crowdloan::statistics().total_claimed, 300 + 400 + 200);
+
+		// Funds handed over to RedistributeToVesters are redistributed as well
+		<impls::RedistributeToVesters<Test> as OnUnbalanced<_>>::on_unbalanced(Balances::issue(
+			150,
+		));
+
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(2)));
+		assert_eq!(Balances::free_balance(1), 800 + 400 + 100);
+		assert_eq!(Balances::free_balance(2), 100 + 400 + 150);
+
+		// Fully claimed entries do not share the redistributed rewards anymore
+		assert_eq!(Crowdloan::total_redistribution_shares(), 0);
+		Balances::make_free_balance_be(&10, 1);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 1));
+		assert_noop!(
+			Crowdloan::redistribute_excess(RuntimeOrigin::root(), 1),
+			Error::<Test>::NothingToRedistribute
+		);
+		assert_eq!(Crowdloan::outstanding_rewards(), 1000);
+
+		let expected = vec![
+			crate::Event::RewardsRedistributed(300),
+			crate::Event::RedistributedRewardsPaid(1, 200),
+			crate::Event::RewardsPaid(1, 400),
+			crate::Event::RewardsRedistributed(150),
+			crate::Event::RedistributedRewardsPaid(1, 100),
+			crate::Event::RewardsPaid(1, 400),
+			crate::Event::RedistributedRewardsPaid(2, 150),
+			crate::Event::RewardsPaid(2, 400),
+		];
+		assert_eq!(
+			events()
+				.into_iter()
+				.filter(|event| matches!(
+					event,
+					crate::Event::RewardsRedistributed(..)
+						| crate::Event::RedistributedRewardsPaid(..)
+						| crate::Event::RewardsPaid(..)
+				))
+				.collect::<Vec<_>>(),
+			expected
+		);
+	});
+}
+
+#[test]
+fn unassociated_rewards_are_swept_at_vesting_end() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), Some(2), 1000u32.into()),
+				([3u8; 32].into(), None, 500u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// The relay account has the whole vesting period to associate
+		roll_to(init_block + 4);
+		assert_noop!(
+			Crowdloan::sweep_unassociated(RuntimeOrigin::root(), vec![[3u8; 32]]),
+			Error::<Test>::SweepBeforeVestingEnd
+		);
+
+		roll_to(init_block + VESTING);
+		assert_noop!(
+			Crowdloan::sweep_unassociated(RuntimeOrigin::signed(1), vec![[3u8; 32]]),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Crowdloan::sweep_unassociated(RuntimeOrigin::root(), vec![[1u8; 32]]),
+			Error::<Test>::NoAssociatedClaim
+		);
+		assert_ok!(Crowdloan::sweep_unassociated(
+			RuntimeOrigin::root(),
+			vec![[3u8; 32]]
+		));
+		assert!(Crowdloan::unassociated_contributions(&[3u8; 32]).is_none());
+		assert!(Crowdloan::relay_rewards(&[3u8; 32]).is_none());
+		assert_eq!(Crowdloan::statistics().unassociated_rewards, 0);
+		assert_eq!(Crowdloan::total_contributors(), 2);
+		assert!(Crowdloan::is_contribution_initialized(&[3u8; 32]));
+
+		// The swept rewards are owed to the associated entries now
+		assert_eq!(Crowdloan::outstanding_rewards(), 2100);
+		assert_eq!(Crowdloan::excess_funds(), 0);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 200 + 800 + 250);
+
+		let expected = vec![
+			crate::Event::UnassociatedRewardsSwept(1, 500),
+			crate::Event::RewardsRedistributed(500),
+		];
+		assert!(events()
+			.windows(2)
+			.any(|window| window == expected.as_slice()));
+
+		// A swept relay account cannot be initialized again in a supplemental round
+		Balances::make_free_balance_be(&10, 500);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 500));
+		assert_ok!(Crowdloan::open_supplemental_round(
+			RuntimeOrigin::root(),
+			500,
+			None
+		));
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([3u8; 32].into(), Some(3), 500u32.into())]
+		));
+		assert!(Crowdloan::accounts_payable(&3).is_none());
+		assert_eq!(Crowdloan::total_contributors(), 2);
+		let expected = vec![
+			crate::Event::InitializedAlreadyInitializedAccount([3u8; 32], Some(3), 500),
+			crate::Event::InitializationBatchProcessed(0, 1, 0, 0, 0),
+		];
+		assert!(events().ends_with(&expected));
+	});
+}
+
+#[test]
+fn claims_and_associations_with_a_payout_are_feeless() {
+	let pairs = get_ed25519_pairs(2);
+	let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+	payload.append(&mut TestSigantureNetworkIdentifier::get().to_vec());
+	payload.append(&mut 3u64.encode());
+	payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+	let signature: MultiSignature = pairs[0].sign(&payload).into();
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				(pairs[0].public().into(), None, 1000u32.into()),
+				(pairs[1].public().into(), None, 500u32.into()),
+			]
+		));
+
+		let claim = Call::<Test>::claim {};
+		let associate =
+			|reward_account: u64, proof: MultiSignature| Call::<Test>::associate_native_identity {
+				reward_account,
+				relay_account: pairs[0].public().into(),
+				proof,
+			};
+
+		// Nothing can be claimed before the initialization is completed
+		assert!(!claim.is_feeless(&RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		roll_to(init_block + 2);
+		assert!(claim.is_feeless(&RuntimeOrigin::signed(1)));
+		// No entry
+		assert!(!claim.is_feeless(&RuntimeOrigin::signed(2)));
+		assert!(!claim.is_feeless(&RuntimeOrigin::root()));
+
+		// Rate limited after a claim
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		roll_to(init_block + 4);
+		assert!(!claim.is_feeless(&RuntimeOrigin::signed(1)));
+		roll_to(init_block + 5);
+		assert!(claim.is_feeless(&RuntimeOrigin::signed(1)));
+
+		// Nothing vested left
+		roll_to(init_block + VESTING);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		roll_to(init_block + VESTING + 4);
+		assert!(!claim.is_feeless(&RuntimeOrigin::signed(1)));
+
+		// Associations need a valid proof
+		let wrong_signature: MultiSignature = pairs[1].sign(&payload).into();
+		assert!(!associate(3, wrong_signature).is_feeless(&RuntimeOrigin::signed(4)));
+		assert!(!associate(4, signature.clone()).is_feeless(&RuntimeOrigin::signed(4)));
+		assert!(associate(3, signature.clone()).is_feeless(&RuntimeOrigin::signed(4)));
+
+		// Every relay account can only be associated once
+		assert_ok!(Crowdloan::associate_native_identity(
+			RuntimeOrigin::signed(4),
+			3,
+			pairs[0].public().into(),
+			signature.clone()
+		));
+		assert!(!associate(3, signature).is_feeless(&RuntimeOrigin::signed(4)));
+	});
+}
+
+#[test]
+fn associations_with_an_initial_payment_below_existential_deposit_are_not_feeless() {
+	let pairs = get_ed25519_pairs(1);
+	let mut payload = WRAPPED_BYTES_PREFIX.to_vec();
+	payload.append(&mut TestSigantureNetworkIdentifier::get().to_vec());
+	payload.append(&mut 3u64.encode());
+	payload.append(&mut WRAPPED_BYTES_POSTFIX.to_vec());
+	let signature: MultiSignature = pairs[0].sign(&payload).into();
+	empty().execute_with(|| {
+		ExistentialDeposit::set(50);
+		roll_to(2);
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				(pairs[0].public().into(), None, 100u32.into()),
+				([2u8; 32].into(), Some(2), 2400u32.into()),
+			]
+		));
+		let associate = Call::<Test>::associate_native_identity {
+			reward_account: 3,
+			relay_account: pairs[0].public().into(),
+			proof: signature.clone(),
+		};
+
+		// The initial payment of 20 would be skipped
+		assert!(!associate.is_feeless(&RuntimeOrigin::signed(4)));
+
+		// It is made once the account holds the existential deposit
+		Balances::make_free_balance_be(&3, 50);
+		assert!(associate.is_feeless(&RuntimeOrigin::signed(4)));
+		assert_ok!(Crowdloan::associate_native_identity(
+			RuntimeOrigin::signed(4),
+			3,
+			pairs[0].public().into(),
+			signature
+		));
+		assert_eq!(Balances::free_balance(3), 70);
+	});
+}
+
+#[test]
+fn failing_claims_are_not_feeless() {
+	empty().execute_with(|| {
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), None, 1500u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		let claim = Call::<Test>::claim {};
+
+		// The pot cannot pay, so the claims fail and leave no claim record behind to rate limit
+		// the next ones
+		Balances::set_lock(
+			*b"testlock",
+			&Crowdloan::account_id(),
+			Crowdloan::pot(),
+			WithdrawReasons::all(),
+		);
+		for block in 2..5 {
+			roll_to(init_block + block);
+			assert!(!claim.is_feeless(&RuntimeOrigin::signed(1)));
+			assert!(Crowdloan::claim(RuntimeOrigin::signed(1)).is_err());
+			assert!(Crowdloan::claim_records(&1).is_none());
+		}
+
+		Balances::remove_lock(*b"testlock", &Crowdloan::account_id());
+		assert!(claim.is_feeless(&RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 200 + 400);
+	});
+}
+
+#[test]
+fn initial_payments_below_existential_deposit_are_skipped() {
+	empty().execute_with(|| {
+		ExistentialDeposit::set(50);
+		roll_to(2);
+		// The initial payment of 1 is below the existential deposit, but the batch goes through
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 100u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				([3u8; 32].into(), None, 1800u32.into()),
+			]
+		));
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 0);
+		assert_eq!(Crowdloan::init_reward_amount(), 2300);
+
+		// An account already holding the existential deposit gets its initial payment
+		Balances::make_free_balance_be(&4, 50);
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([4u8; 32].into(), Some(4), 100u32.into())]
+		));
+		assert_eq!(Balances::free_balance(4), 70);
+
+		let expected = vec![
+			crate::Event::InitialPaymentSkipped(1, 20),
+			crate::Event::InitialPaymentMade(2, 100),
+			crate::Event::InitializationBatchProcessed(3, 0, 0, 0, 2400),
+			crate::Event::InitialPaymentMade(4, 20),
+			crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 100),
+		];
+		assert_eq!(events(), expected);
+	});
+}
+
+#[test]
+fn payouts_below_existential_deposit_accumulate() {
+	empty().execute_with(|| {
+		ExistentialDeposit::set(50);
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 100u32.into()),
+				([2u8; 32].into(), None, 2400u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// The skipped initial payment and the reward vested so far are below the existential
+		// deposit
+		roll_to(init_block + 1);
+		assert!(!Call::<Test>::claim {}.is_feeless(&RuntimeOrigin::signed(1)));
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 0);
+
+		// Nothing is staked when the payout is deferred
+		assert_ok!(Crowdloan::claim_and_stake(RuntimeOrigin::signed(1), 7));
+		assert!(Staked::get().is_empty());
+
+		// Exactly the existential deposit can be paid
+		roll_to(init_block + 3);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 50);
+
+		// Once the account exists, any payout goes through
+		roll_to(init_block + 4);
+		assert_ok!(Crowdloan::claim(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 60);
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 60);
+
+		let expected = vec![
+			crate::Event::PayoutDeferred(1, 30),
+			crate::Event::PayoutDeferred(1, 30),
+			crate::Event::RewardsPaid(1, 50),
+			crate::Event::RewardsPaid(1, 10),
+		];
+		assert_eq!(
+			events()
+				.into_iter()
+				.filter(|event| matches!(
+					event,
+					crate::Event::PayoutDeferred(..)
+						| crate::Event::RewardsPaid(..)
+						| crate::Event::RewardsStaked(..)
+				))
+				.collect::<Vec<_>>(),
+			expected
+		);
+	});
+}
+
+#[test]
+fn early_exits_below_existential_deposit_are_deferred() {
+	empty().execute_with(|| {
+		ExistentialDeposit::set(50);
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 40u32.into()),
+				([2u8; 32].into(), None, 2460u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+
+		// The skipped initial payment, the 16 tokens vested so far and the 12 unvested tokens
+		// left after the penalty are below the existential deposit
+		roll_to(init_block + 4);
+		assert_ok!(Crowdloan::exit_early(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::free_balance(PenaltyAccount::get()), 0);
+		assert_eq!(Crowdloan::forfeited_reward_amount(), 0);
+		let reward_info = Crowdloan::accounts_payable(&1).unwrap();
+		assert_eq!(reward_info.total_reward, 40);
+		assert_eq!(reward_info.claimed_reward, 0);
+		assert_eq!(events().last(), Some(&crate::Event::PayoutDeferred(1, 36)));
+
+		// Once the account exists the entry can be closed
+		Balances::make_free_balance_be(&1, 50);
+		assert_ok!(Crowdloan::exit_early(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 86);
+		assert_eq!(Crowdloan::forfeited_reward_amount(), 4);
+		assert_eq!(events().last(), Some(&crate::Event::ExitedEarly(1, 36, 4)));
+	});
+}
+
+#[test]
+fn redistributed_rewards_count_towards_the_existential_deposit() {
+	empty().execute_with(|| {
+		ExistentialDeposit::set(50);
+		roll_to(2);
+		let init_block = Crowdloan::init_vesting_block();
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), Some(2), 1500u32.into()),
+			]
+		));
+		assert_ok!(Crowdloan::complete_initialization(
+			RuntimeOrigin::root(),
+			init_block + VESTING,
+			None
+		));
+		Balances::make_free_balance_be(&10, 300);
+		assert_ok!(Crowdloan::fund_pot(RuntimeOrigin::root(), 10, 250));
+		assert_ok!(Crowdloan::redistribute_excess(RuntimeOrigin::root(), 250));
+
+		// Neither the claimed amount nor the redistributed rewards reach the existential deposit
+		// of an empty account alone, but their sum does
+		ExistentialDeposit::set(150);
+		roll_to(init_block + 1);
+		assert_ok!(Crowdloan::claim_to(RuntimeOrigin::signed(1), 9, Some(60)));
+		assert_eq!(Balances::free_balance(9), 160);
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 260);
+
+		// Without redistributed rewards left, the payout is deferred
+		assert_ok!(Crowdloan::claim_to(RuntimeOrigin::signed(1), 8, Some(40)));
+		assert_eq!(Balances::free_balance(8), 0);
+		assert_eq!(Crowdloan::accounts_payable(&1).unwrap().claimed_reward, 260);
+
+		let expected = vec![
+			crate::Event::RedistributedRewardsPaid(9, 100),
+			crate::Event::RewardsPaidTo(1, 9, 60),
+			crate::Event::PayoutDeferred(8, 40),
+		];
+		assert_eq!(
+			events()
+				.into_iter()
+				.filter(|event| matches!(
+					event,
+					crate::Event::RedistributedRewardsPaid(..)
+						| crate::Event::RewardsPaidTo(..)
+						| crate::Event::PayoutDeferred(..)
+				))
+				.collect::<Vec<_>>(),
+			expected
+		);
+	});
+}
+
+#[test]
+fn failed_initial_payments_do_not_abort_the_batch() {
+	empty().execute_with(|| {
+		TestMinimumReward::set(10);
+		roll_to(2);
+		// Only one initial payment can be made out of the pot
+		Balances::set_lock(
+			*b"testlock",
+			&Crowdloan::account_id(),
+			2400,
+			WithdrawReasons::all(),
+		);
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([2u8; 32].into(), Some(2), 500u32.into()),
+				([1u8; 32].into(), Some(1), 500u32.into()),
+				([4u8; 32].into(), None, 5u32.into()),
+				([5u8; 32].into(), None, 900u32.into()),
+			]
+		));
+
+		// Nothing is stored for the entry whose initial payment failed
+		assert!(Crowdloan::accounts_payable(&2).is_none());
+		assert!(Crowdloan::relay_rewards(&[2u8; 32]).is_none());
+		assert_eq!(Balances::free_balance(2), 0);
+		assert_eq!(Crowdloan::total_contributors(), 2);
+		assert_eq!(Crowdloan::init_reward_amount(), 1300);
+		assert!(events().iter().any(|event| matches!(
+			event,
+			crate::Event::InitialPaymentFailed([2u8; 32], Some(2), 500, _)
+		)));
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::InitializationBatchProcessed(
+				2, 1, 1, 1, 1400
+			))
+		);
+
+		// The entry can be initialized in a later batch
+		Balances::remove_lock(*b"testlock", &Crowdloan::account_id());
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![([2u8; 32].into(), Some(2), 500u32.into())]
+		));
+		assert_eq!(Balances::free_balance(2), 100);
+		assert_eq!(Crowdloan::accounts_payable(&2).unwrap().total_reward, 500);
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::InitializationBatchProcessed(1, 0, 0, 0, 500))
+		);
+	});
+}
+
+#[test]
+fn resent_batches_only_need_their_new_entries_to_fit() {
+	empty().execute_with(|| {
+		roll_to(2);
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), Some(2), 1000u32.into()),
+			]
+		));
+		// The initial payments were made out of the pot
+		assert_eq!(Crowdloan::pot(), 2100);
+		assert_eq!(Crowdloan::init_reward_amount(), 1600);
+
+		// The new entry does not fit in what is left
+		assert_noop!(
+			Crowdloan::initialize_reward_vec(
+				RuntimeOrigin::root(),
+				vec![
+					([1u8; 32].into(), Some(1), 1000u32.into()),
+					([2u8; 32].into(), Some(2), 1000u32.into()),
+					([3u8; 32].into(), Some(3), 1000u32.into()),
+				]
+			),
+			Error::<Test>::BatchBeyondFundPot
+		);
+
+		// Entries already initialized do not count towards the funds
+		assert_ok!(Crowdloan::initialize_reward_vec(
+			RuntimeOrigin::root(),
+			vec![
+				([1u8; 32].into(), Some(1), 1000u32.into()),
+				([2u8; 32].into(), Some(2), 1000u32.into()),
+				([3u8; 32].into(), Some(3), 500u32.into()),
+			]
+		));
+		assert_eq!(Crowdloan::accounts_payable(&3).unwrap().total_reward, 500);
+		assert_eq!(Crowdloan::total_contributors(), 3);
+		assert_eq!(
+			events().last(),
+			Some(&crate::Event::InitializationBatchProcessed(1, 2, 0, 0, 500))
+		);
+	});
+}
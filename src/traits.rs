@@ -0,0 +1,110 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Traits to plug other pallets into the crowdloan rewards pallet
+
+use frame_support::{weights::Weight, Parameter};
+use sp_core::H256;
+use sp_runtime::{DispatchError, DispatchResult, Perbill, Perquintill};
+
+/// Hooks for other pallets to react to what happens to the reward entries
+/// Every hook does nothing by default
+pub trait OnRewardEvent<AccountId, RelayChainAccountId, Balance> {
+	/// Rewards were paid to an account, either as an initial payment or through a claim.
+	/// `who` is the account receiving the funds, and `amount` includes the redistributed rewards
+	/// and the bonuses paid along with the claim
+	fn on_reward_paid(_who: &AccountId, _amount: Balance) {}
+
+	/// A relay account was associated with a native account.
+	/// Data is the relay account, the native account and the total reward of the entry
+	fn on_identity_associated(
+		_relay_account: &RelayChainAccountId,
+		_who: &AccountId,
+		_total_reward: Balance,
+	) {
+	}
+
+	/// The reward address of an entry was changed from `previous` to `new`
+	fn on_reward_address_updated(_previous: &AccountId, _new: &AccountId) {}
+
+	/// The maximum weight consumed by a single call to any of the hooks
+	fn weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+impl<AccountId, RelayChainAccountId, Balance> OnRewardEvent<AccountId, RelayChainAccountId, Balance>
+	for ()
+{
+}
+
+/// Stakes claimed rewards on behalf of their owner, eg delegating them to a collator or bonding
+/// them in a nomination pool
+pub trait StakingAdapter<AccountId, Balance> {
+	/// What the rewards are staked with, eg a collator account or a pool id
+	type Target: Parameter;
+
+	/// Stake `amount` out of the free balance of `who` with `target`
+	fn stake(who: &AccountId, target: Self::Target, amount: Balance) -> DispatchResult;
+
+	/// The maximum weight consumed by stake
+	fn weight() -> Weight;
+}
+
+/// Staking is not supported, so claim_and_stake always fails
+impl<AccountId, Balance> StakingAdapter<AccountId, Balance> for () {
+	type Target = ();
+
+	fn stake(_who: &AccountId, _target: (), _amount: Balance) -> DispatchResult {
+		Err(DispatchError::Other("Staking is not supported"))
+	}
+
+	fn weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Provides the relay chain state root that snapshot_relay_state_root pins for relay state
+/// proofs to be verified against, eg the storage root of the relay parent in the parachain
+/// validation data
+pub trait RelayStateRootProvider {
+	/// The relay state root, if known
+	fn relay_state_root() -> Option<H256>;
+
+	/// Set the relay state root returned afterwards, used by the benchmarks
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_relay_state_root(_root: H256) {}
+}
+
+/// There is no relay state root, so relay state proofs cannot be verified
+impl RelayStateRootProvider for () {
+	fn relay_state_root() -> Option<H256> {
+		None
+	}
+}
+
+/// Converts the contribution of a relay account, in relay chain tokens, into its reward
+pub trait RewardConversion<Balance> {
+	/// The reward paid for contributing `contribution`
+	fn convert(contribution: u128) -> Balance;
+}
+
+/// The penalty on the unvested reward of entries exiting early with exit_early
+pub trait EarlyExitPenalty {
+	/// The fraction of the unvested reward forfeited, given the fraction of the vesting period
+	/// already elapsed
+	fn penalty(elapsed: Perquintill) -> Perbill;
+}
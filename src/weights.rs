@@ -19,6 +19,10 @@
 //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
 //! DATE: 2021-08-01, STEPS: `[32, ]`, REPEAT: 64, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//!
+//! The weights marked as placeholders are estimates that were not generated by the benchmarks
+//! in `benchmarks.rs`. They need to be replaced by the output of the command below before
+//! being used in a runtime.
 
 // Executed Command:
 // ./target/release/moonbeam
@@ -57,29 +61,49 @@ pub trait WeightInfo {
 	fn update_reward_address() -> Weight;
 	fn associate_native_identity() -> Weight;
 	fn change_association_with_relay_keys(x: u32) -> Weight;
+	fn split_reward_entry(x: u32, y: u32) -> Weight;
+	fn approve_merge() -> Weight;
+	fn merge_reward_entries(x: u32, y: u32) -> Weight;
+	fn set_init_vesting_block() -> Weight;
+	fn fund_pot() -> Weight;
+	fn withdraw_excess() -> Weight;
+	fn reset_campaign(x: u32) -> Weight;
+	fn open_supplemental_round() -> Weight;
+	fn complete_supplemental_round() -> Weight;
+	fn close_supplemental_round() -> Weight;
+	fn ingest_relay_contributions(x: u32, y: u32) -> Weight;
+	fn initialize_contributions(x: u32) -> Weight;
+	fn set_reward_curve(x: u32) -> Weight;
+	fn initialize_reward_entries(x: u32) -> Weight;
+	fn fund_bonus_pot() -> Weight;
+	fn exit_early() -> Weight;
+	fn redistribute_excess() -> Weight;
+	fn sweep_unassociated(x: u32) -> Weight;
+	fn snapshot_relay_state_root() -> Weight;
 }
 
 /// Weights for pallet_crowdloan_rewards using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	fn initialize_reward_vec(x: u32) -> Weight {
-		Weight::from_all(143_109_000)
-			// Standard Error: 21_000
-			.saturating_add(Weight::from_all(72_298_000_u64.saturating_mul(x as u64)))
-			.saturating_add(T::DbWeight::get().reads(8))
-			.saturating_add(T::DbWeight::get().reads(x as u64))
-			.saturating_add(T::DbWeight::get().writes(5))
-			.saturating_add(T::DbWeight::get().writes(3 as u64))
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(168_412_000)
+			.saturating_add(Weight::from_all(98_574_000_u64.saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((9 as u64).saturating_mul(x as u64)))
 	}
 	fn complete_initialization() -> Weight {
 		Weight::from_all(51_047_000)
 			.saturating_add(T::DbWeight::get().reads(6))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	fn claim() -> Weight {
-		Weight::from_all(101_484_000)
-			.saturating_add(T::DbWeight::get().reads(11))
-			.saturating_add(T::DbWeight::get().writes(5))
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(164_218_000)
+			.saturating_add(T::DbWeight::get().reads(15))
+			.saturating_add(T::DbWeight::get().writes(10))
 	}
 	fn update_reward_address() -> Weight {
 		Weight::from_all(59_051_000)
@@ -87,9 +111,10 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	fn associate_native_identity() -> Weight {
-		Weight::from_all(152_997_000)
-			.saturating_add(T::DbWeight::get().reads(9))
-			.saturating_add(T::DbWeight::get().writes(7))
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(171_356_000)
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(8))
 	}
 	fn change_association_with_relay_keys(x: u32) -> Weight {
 		Weight::from_all(0)
@@ -98,28 +123,165 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(6))
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	fn split_reward_entry(x: u32, y: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(38_512_000)
+			.saturating_add(Weight::from_all(47_373_000_u64.saturating_mul(x as u64)))
+			.saturating_add(Weight::from_all(21_846_000_u64.saturating_mul(y as u64)))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(y as u64)))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes(y as u64))
+	}
+	fn approve_merge() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(24_180_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn merge_reward_entries(x: u32, y: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(41_206_000)
+			.saturating_add(Weight::from_all(47_373_000_u64.saturating_mul(x as u64)))
+			.saturating_add(Weight::from_all(47_373_000_u64.saturating_mul(y as u64)))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn set_init_vesting_block() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(15_413_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn fund_pot() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(58_207_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn withdraw_excess() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(61_932_000)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn reset_campaign(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(72_416_000)
+			.saturating_add(Weight::from_all(64_810_000_u64.saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(9))
+			.saturating_add(T::DbWeight::get().writes((6 as u64).saturating_mul(x as u64)))
+	}
+	fn open_supplemental_round() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(27_615_000)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn complete_supplemental_round() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(49_308_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn close_supplemental_round() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(21_540_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn ingest_relay_contributions(x: u32, y: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(151_884_000)
+			.saturating_add(Weight::from_all(91_417_000_u64.saturating_mul(x as u64)))
+			.saturating_add(Weight::from_all(2_100_u64.saturating_mul(y as u64)))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((4 as u64).saturating_mul(x as u64)))
+	}
+	fn initialize_contributions(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(171_046_000)
+			.saturating_add(Weight::from_all(101_233_000_u64.saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((9 as u64).saturating_mul(x as u64)))
+	}
+	fn set_reward_curve(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(18_204_000)
+			.saturating_add(Weight::from_all(152_000_u64.saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn initialize_reward_entries(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(176_518_000)
+			.saturating_add(Weight::from_all(113_072_000_u64.saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().reads((9 as u64).saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((11 as u64).saturating_mul(x as u64)))
+	}
+	fn fund_bonus_pot() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(52_407_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn exit_early() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(132_906_000)
+			.saturating_add(T::DbWeight::get().reads(14))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	fn redistribute_excess() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(38_214_000)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn sweep_unassociated(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(42_186_000)
+			.saturating_add(Weight::from_all(21_473_000_u64.saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads(x as u64))
+			.saturating_add(T::DbWeight::get().writes(6))
+			.saturating_add(T::DbWeight::get().writes((4 as u64).saturating_mul(x as u64)))
+	}
+	fn snapshot_relay_state_root() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(12_307_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
 	fn initialize_reward_vec(x: u32) -> Weight {
-		Weight::from_all(143_109_000)
-			// Standard Error: 21_000
-			.saturating_add(Weight::from_all(72_298_000_u64.saturating_mul(x as u64)))
-			.saturating_add(RocksDbWeight::get().reads(8))
-			.saturating_add(RocksDbWeight::get().reads(x as u64))
-			.saturating_add(RocksDbWeight::get().writes(5))
-			.saturating_add(RocksDbWeight::get().writes(x as u64))
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(168_412_000)
+			.saturating_add(Weight::from_all(98_574_000_u64.saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().reads((8 as u64).saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((9 as u64).saturating_mul(x as u64)))
 	}
 	fn complete_initialization() -> Weight {
 		Weight::from_all(51_047_000)
 			.saturating_add(RocksDbWeight::get().reads(6))
-			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(RocksDbWeight::get().writes(4))
 	}
 	fn claim() -> Weight {
-		Weight::from_all(101_484_000)
-			.saturating_add(RocksDbWeight::get().reads(11))
-			.saturating_add(RocksDbWeight::get().writes(5))
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(164_218_000)
+			.saturating_add(RocksDbWeight::get().reads(15))
+			.saturating_add(RocksDbWeight::get().writes(10))
 	}
 	fn update_reward_address() -> Weight {
 		Weight::from_all(59_051_000)
@@ -127,9 +289,10 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(4))
 	}
 	fn associate_native_identity() -> Weight {
-		Weight::from_all(152_997_000)
-			.saturating_add(RocksDbWeight::get().reads(9))
-			.saturating_add(RocksDbWeight::get().writes(7))
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(171_356_000)
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().writes(8))
 	}
 	fn change_association_with_relay_keys(x: u32) -> Weight {
 		Weight::from_all(0)
@@ -138,4 +301,140 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(6))
 			.saturating_add(RocksDbWeight::get().writes(4))
 	}
+	fn split_reward_entry(x: u32, y: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(38_512_000)
+			.saturating_add(Weight::from_all(47_373_000_u64.saturating_mul(x as u64)))
+			.saturating_add(Weight::from_all(21_846_000_u64.saturating_mul(y as u64)))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(y as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(RocksDbWeight::get().writes(y as u64))
+	}
+	fn approve_merge() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(24_180_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn merge_reward_entries(x: u32, y: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(41_206_000)
+			.saturating_add(Weight::from_all(47_373_000_u64.saturating_mul(x as u64)))
+			.saturating_add(Weight::from_all(47_373_000_u64.saturating_mul(y as u64)))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn set_init_vesting_block() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(15_413_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn fund_pot() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(58_207_000)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn withdraw_excess() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(61_932_000)
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn reset_campaign(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(72_416_000)
+			.saturating_add(Weight::from_all(64_810_000_u64.saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().reads(8))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().writes(9))
+			.saturating_add(RocksDbWeight::get().writes((6 as u64).saturating_mul(x as u64)))
+	}
+	fn open_supplemental_round() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(27_615_000)
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn complete_supplemental_round() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(49_308_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn close_supplemental_round() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(21_540_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn ingest_relay_contributions(x: u32, y: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(151_884_000)
+			.saturating_add(Weight::from_all(91_417_000_u64.saturating_mul(x as u64)))
+			.saturating_add(Weight::from_all(2_100_u64.saturating_mul(y as u64)))
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((4 as u64).saturating_mul(x as u64)))
+	}
+	fn initialize_contributions(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(171_046_000)
+			.saturating_add(Weight::from_all(101_233_000_u64.saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().reads((8 as u64).saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((9 as u64).saturating_mul(x as u64)))
+	}
+	fn set_reward_curve(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(18_204_000)
+			.saturating_add(Weight::from_all(152_000_u64.saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn initialize_reward_entries(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(176_518_000)
+			.saturating_add(Weight::from_all(113_072_000_u64.saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().reads((9 as u64).saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((11 as u64).saturating_mul(x as u64)))
+	}
+	fn fund_bonus_pot() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(52_407_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn exit_early() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(132_906_000)
+			.saturating_add(RocksDbWeight::get().reads(14))
+			.saturating_add(RocksDbWeight::get().writes(9))
+	}
+	fn redistribute_excess() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(38_214_000)
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn sweep_unassociated(x: u32) -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(42_186_000)
+			.saturating_add(Weight::from_all(21_473_000_u64.saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads(x as u64))
+			.saturating_add(RocksDbWeight::get().writes(6))
+			.saturating_add(RocksDbWeight::get().writes((4 as u64).saturating_mul(x as u64)))
+	}
+	fn snapshot_relay_state_root() -> Weight {
+		// Placeholder, not generated by the benchmarks yet
+		Weight::from_all(12_307_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
 }